@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zkenc_cli::codec::CombinedCiphertext;
+
+/// Feed arbitrary bytes through the combined-ciphertext decoder. It must
+/// never panic - a malformed or truncated buffer should always come back
+/// as a clean `Err`, since this format is meant to cross an untrusted
+/// channel before `decrypt_command` ever sees it.
+fn do_test(data: &[u8]) {
+    let _ = CombinedCiphertext::decode(data);
+}
+
+fuzz_target!(|data: &[u8]| {
+    do_test(data);
+});
@@ -1,7 +1,9 @@
+use crate::circom;
 use crate::r1cs::R1csFile;
-use anyhow::Result;
-use ark_bn254::Fr; // Circom uses BN254 (alt_bn128)
-use ark_ff::PrimeField;
+use crate::sym_parser::{get_input_signals, parse_sym_file};
+use crate::witness::WitnessFile;
+use anyhow::{anyhow, bail, Result};
+use ark_ff::{BigInteger, PrimeField};
 use ark_relations::gr1cs::{
     ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
     R1CS_PREDICATE_LABEL,
@@ -9,17 +11,21 @@ use ark_relations::gr1cs::{
 use ark_std::vec::Vec;
 use std::collections::HashMap;
 use std::path::Path;
+use std::str::FromStr;
 
 /// Circom circuit wrapper that implements ConstraintSynthesizer
 ///
 /// This bridges Circom R1CS format to zkenc-core's ConstraintSynthesizer trait.
-/// Uses BN254 (alt_bn128) curve as this is Circom's default.
-pub struct CircomCircuit {
+/// Generic over the witness field `F`, the same way [`crate::r1cs::R1csSynthesizer`]
+/// and `MiMCCircuit<F>` in zkenc-core's tests are - parsing an R1CS file and
+/// assigning a witness only needs `PrimeField`, not a `Pairing`. Only the
+/// downstream `encap`/`decap` call sites need to pick a concrete curve.
+pub struct CircomCircuit<F: PrimeField> {
     r1cs: R1csFile,
-    witness: HashMap<u32, Fr>, // wire_id -> value
+    witness: HashMap<u32, F>, // wire_id -> value
 }
 
-impl CircomCircuit {
+impl<F: PrimeField> CircomCircuit<F> {
     /// Load a Circom circuit from R1CS file
     pub fn from_r1cs<P: AsRef<Path>>(r1cs_path: P) -> Result<Self> {
         let r1cs = R1csFile::from_file(r1cs_path)?;
@@ -33,15 +39,137 @@ impl CircomCircuit {
     ///
     /// # Arguments
     /// * `values` - Map from wire_id to field element value
-    pub fn set_witness(&mut self, values: HashMap<u32, Fr>) {
+    pub fn set_witness(&mut self, values: HashMap<u32, F>) {
         self.witness = values;
     }
 
+    /// Load an R1CS file together with a snarkjs `.wtns` witness file,
+    /// producing a circuit that is already fully assigned and ready to pass
+    /// into [`zkenc_core::decap`].
+    ///
+    /// Rejects the witness if it was generated for a different field than
+    /// the R1CS (mismatched prime) or if it doesn't assign exactly
+    /// `r1cs.n_wires` values - either would otherwise surface much later as
+    /// an opaque constraint-synthesis or pairing failure.
+    pub fn from_r1cs_and_witness<P: AsRef<Path>>(r1cs_path: P, witness_path: &str) -> Result<Self> {
+        let r1cs = R1csFile::from_file(r1cs_path)?;
+        let witness_file = WitnessFile::from_file(witness_path)?;
+
+        if witness_file.prime != r1cs.prime {
+            bail!(
+                "Witness field prime ({} bytes) does not match R1CS prime ({} bytes)",
+                witness_file.prime.len(),
+                r1cs.prime.len()
+            );
+        }
+        if witness_file.n_wires() != r1cs.n_wires {
+            bail!(
+                "Witness count ({}) does not match R1CS wire count ({})",
+                witness_file.n_wires(),
+                r1cs.n_wires
+            );
+        }
+
+        let witness = witness_file.to_field_elements::<F>()?;
+        Ok(Self { r1cs, witness })
+    }
+
+    /// Load an R1CS file together with its paired circom `.wasm` witness
+    /// generator, computing the full witness in-memory from `inputs` instead
+    /// of requiring a pre-built snarkjs `.wtns` file on disk.
+    ///
+    /// `circom::compute_witness` always runs over BN254 (the only curve
+    /// circom's wasm witness generators target), so this rejects an R1CS
+    /// whose prime doesn't match BN254's scalar field the same way
+    /// [`Self::from_r1cs_and_witness`] rejects a mismatched `.wtns`; each
+    /// computed value is then re-encoded into `F` via [`Self::bytes_to_field`],
+    /// the same path constraint coefficients already go through.
+    pub fn from_r1cs_and_wasm<P: AsRef<Path>>(
+        r1cs_path: P,
+        wasm_path: P,
+        inputs: &HashMap<String, Vec<String>>,
+    ) -> Result<Self> {
+        let r1cs = R1csFile::from_file(r1cs_path)?;
+
+        let bn254_modulus = ark_bn254::Fr::MODULUS.to_bytes_le();
+        if r1cs.prime != bn254_modulus {
+            bail!(
+                "R1CS prime ({} bytes) does not match BN254's scalar field - the wasm witness generator only supports BN254",
+                r1cs.prime.len()
+            );
+        }
+
+        let computed = circom::compute_witness(wasm_path, inputs)?;
+        if computed.len() != r1cs.n_wires as usize {
+            bail!(
+                "Computed witness count ({}) does not match R1CS wire count ({})",
+                computed.len(),
+                r1cs.n_wires
+            );
+        }
+
+        let witness = computed
+            .into_iter()
+            .enumerate()
+            .map(|(wire_id, value)| {
+                (wire_id as u32, Self::bytes_to_field(&value.into_bigint().to_bytes_le()))
+            })
+            .collect();
+
+        Ok(Self { r1cs, witness })
+    }
+
     /// Set a single witness value
-    pub fn set_wire(&mut self, wire_id: u32, value: Fr) {
+    pub fn set_wire(&mut self, wire_id: u32, value: F) {
         self.witness.insert(wire_id, value);
     }
 
+    /// Set witness values from human-readable signal names instead of raw
+    /// wire IDs, the way `CircomBuilder` in other circom toolchains does.
+    ///
+    /// `sym_content` is the contents of the circuit's `.sym` file (maps
+    /// signal names to wire IDs, same format parsed by [`parse_sym_file`]);
+    /// `inputs` maps each top-level signal name (e.g. `"message"`, `"R8x"`)
+    /// to its decimal field-element values - this mirrors the
+    /// `Vec<String>` convention [`crate::circom::parse_inputs`] already
+    /// uses to drive the wasm witness generator, rather than requiring
+    /// callers to hand us wire IDs or a bignum type. A single-element `Vec`
+    /// resolves against the bare signal name; a multi-element `Vec` is
+    /// expanded across `name[0]`, `name[1]`, ... to match the indexed
+    /// entries circom emits for array signals.
+    pub fn set_named_witness(
+        &mut self,
+        sym_content: &str,
+        inputs: &HashMap<String, Vec<String>>,
+    ) -> Result<()>
+    where
+        F: FromStr,
+    {
+        let wire_map = parse_sym_file(sym_content)?;
+        let input_signals = get_input_signals(&wire_map, None);
+
+        self.witness.insert(0, F::one());
+
+        for (name, values) in inputs {
+            for (index, value) in values.iter().enumerate() {
+                let signal_name = if values.len() == 1 {
+                    name.clone()
+                } else {
+                    format!("{name}[{index}]")
+                };
+                let wire_id = *input_signals
+                    .get(&signal_name)
+                    .ok_or_else(|| anyhow!("No wire found for signal '{}'", signal_name))?;
+                let field_value = F::from_str(value).map_err(|_| {
+                    anyhow!("Invalid field element for signal '{}': {}", signal_name, value)
+                })?;
+                self.witness.insert(wire_id, field_value);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Get the number of public inputs
     pub fn n_public_inputs(&self) -> u32 {
         self.r1cs.n_public_inputs()
@@ -53,22 +181,14 @@ impl CircomCircuit {
     }
 
     /// Convert bytes to field element
-    fn bytes_to_fr(bytes: &[u8]) -> Result<Fr, SynthesisError> {
+    fn bytes_to_field(bytes: &[u8]) -> F {
         // R1CS stores field elements in little-endian byte format
-        // We need to convert them to ark-ff's representation
-        Fr::from_le_bytes_mod_order(bytes);
-
-        // Use BigInt conversion for proper handling
-        let mut bytes_array = vec![0u8; 32];
-        let len = bytes.len().min(32);
-        bytes_array[..len].copy_from_slice(&bytes[..len]);
-
-        Ok(Fr::from_le_bytes_mod_order(&bytes_array))
+        F::from_le_bytes_mod_order(bytes)
     }
 }
 
-impl ConstraintSynthesizer<Fr> for CircomCircuit {
-    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+impl<F: PrimeField> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
         // Allocate all variables
         // Wire 0 is always ONE (constant)
         let mut variables: HashMap<u32, Variable> = HashMap::new();
@@ -101,36 +221,33 @@ impl ConstraintSynthesizer<Fr> for CircomCircuit {
 
             // Build closures for A, B, C
             let a_closure = move || {
-                let mut lc = LinearCombination::<Fr>::zero();
+                let mut lc = LinearCombination::<F>::zero();
                 for (wire_id, coeff_bytes) in &a_factors {
-                    if let Ok(coeff) = Self::bytes_to_fr(coeff_bytes) {
-                        if let Some(var) = vars_a.get(wire_id) {
-                            lc = lc + (coeff, *var);
-                        }
+                    let coeff = Self::bytes_to_field(coeff_bytes);
+                    if let Some(var) = vars_a.get(wire_id) {
+                        lc = lc + (coeff, *var);
                     }
                 }
                 lc
             };
 
             let b_closure = move || {
-                let mut lc = LinearCombination::<Fr>::zero();
+                let mut lc = LinearCombination::<F>::zero();
                 for (wire_id, coeff_bytes) in &b_factors {
-                    if let Ok(coeff) = Self::bytes_to_fr(coeff_bytes) {
-                        if let Some(var) = vars_b.get(wire_id) {
-                            lc = lc + (coeff, *var);
-                        }
+                    let coeff = Self::bytes_to_field(coeff_bytes);
+                    if let Some(var) = vars_b.get(wire_id) {
+                        lc = lc + (coeff, *var);
                     }
                 }
                 lc
             };
 
             let c_closure = move || {
-                let mut lc = LinearCombination::<Fr>::zero();
+                let mut lc = LinearCombination::<F>::zero();
                 for (wire_id, coeff_bytes) in &c_factors {
-                    if let Ok(coeff) = Self::bytes_to_fr(coeff_bytes) {
-                        if let Some(var) = vars_c.get(wire_id) {
-                            lc = lc + (coeff, *var);
-                        }
+                    let coeff = Self::bytes_to_field(coeff_bytes);
+                    if let Some(var) = vars_c.get(wire_id) {
+                        lc = lc + (coeff, *var);
                     }
                 }
                 lc
@@ -138,7 +255,7 @@ impl ConstraintSynthesizer<Fr> for CircomCircuit {
 
             // For R1CS: A * B = C means we need to enforce A * B - C = 0
             // gr1cs uses predicate format, we use arity 3 with standard R1CS_PREDICATE_LABEL
-            let boxed: Vec<Box<dyn FnOnce() -> LinearCombination<Fr>>> = vec![
+            let boxed: Vec<Box<dyn FnOnce() -> LinearCombination<F>>> = vec![
                 Box::new(a_closure),
                 Box::new(b_closure),
                 Box::new(c_closure),
@@ -153,13 +270,15 @@ impl ConstraintSynthesizer<Fr> for CircomCircuit {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ark_bn254::Fr;
     use ark_ff::Zero;
     use std::path::PathBuf;
 
     #[test]
     fn test_load_circom_circuit() {
         let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
-        let circuit = CircomCircuit::from_r1cs(&r1cs_path).expect("Failed to load circuit");
+        let circuit =
+            CircomCircuit::<Fr>::from_r1cs(&r1cs_path).expect("Failed to load circuit");
 
         println!("Circom Circuit:");
         println!("  Public inputs: {}", circuit.n_public_inputs());
@@ -169,10 +288,76 @@ mod tests {
         assert_eq!(circuit.n_constraints(), 8443);
     }
 
+    #[test]
+    #[ignore] // Only run when a matching witness fixture is available
+    fn test_from_r1cs_and_witness() {
+        let witness_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/inputs/signature_signature_basic.wtns"
+        );
+        let circuit =
+            CircomCircuit::<Fr>::from_r1cs_and_witness("tests/r1cs/signature.r1cs", witness_path)
+                .expect("Failed to load circuit and witness");
+
+        assert_eq!(circuit.n_public_inputs(), 7);
+        assert_eq!(circuit.n_constraints(), 8443);
+    }
+
+    #[test]
+    fn test_set_named_witness_expands_indexed_signals() {
+        let sym_content = "\
+1,1,172,main.message
+2,2,172,main.R8x[0]
+3,3,172,main.R8x[1]
+-1,-1,172,main.internal";
+
+        let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
+        let mut circuit =
+            CircomCircuit::<Fr>::from_r1cs(&r1cs_path).expect("Failed to load circuit");
+
+        let mut inputs = HashMap::new();
+        inputs.insert("message".to_string(), vec!["5".to_string()]);
+        inputs.insert("R8x".to_string(), vec!["10".to_string(), "20".to_string()]);
+
+        circuit
+            .set_named_witness(sym_content, &inputs)
+            .expect("Failed to set named witness");
+
+        assert_eq!(circuit.witness.get(&0), Some(&Fr::from(1u64)));
+        assert_eq!(circuit.witness.get(&1), Some(&Fr::from(5u64)));
+        assert_eq!(circuit.witness.get(&2), Some(&Fr::from(10u64)));
+        assert_eq!(circuit.witness.get(&3), Some(&Fr::from(20u64)));
+    }
+
+    #[test]
+    fn test_set_named_witness_rejects_unknown_signal() {
+        let sym_content = "1,1,172,main.message";
+
+        let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
+        let mut circuit =
+            CircomCircuit::<Fr>::from_r1cs(&r1cs_path).expect("Failed to load circuit");
+
+        let mut inputs = HashMap::new();
+        inputs.insert("nonexistent".to_string(), vec!["1".to_string()]);
+
+        let result = circuit.set_named_witness(sym_content, &inputs);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_from_r1cs_and_witness_rejects_non_witness_file() {
+        let result = CircomCircuit::<Fr>::from_r1cs_and_witness(
+            "tests/r1cs/signature.r1cs",
+            "tests/r1cs/signature.r1cs", // wrong magic bytes, not a .wtns file
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_circuit_synthesis() {
         let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
-        let mut circuit = CircomCircuit::from_r1cs(&r1cs_path).expect("Failed to load circuit");
+        let mut circuit =
+            CircomCircuit::<Fr>::from_r1cs(&r1cs_path).expect("Failed to load circuit");
 
         // Set dummy witness values (all zeros for now)
         for wire_id in 0..circuit.r1cs.n_wires {
@@ -0,0 +1,442 @@
+//! A small, versioned, trait-based wire codec for the combined-ciphertext
+//! container, replacing the fixed `u32` length prefixes and ad-hoc flag byte
+//! that used to be assembled/disassembled inline in `commands.rs`.
+//!
+//! [`Encodable`]/[`Decodable`] play the same role as Bitcoin's consensus
+//! encoding traits: any type that knows how to read/write its own wire
+//! format implements them, and composite types (like [`CombinedCiphertext`])
+//! just call into their fields' impls. Section lengths are encoded with
+//! [`VarInt`] (Bitcoin's `CompactSize`) instead of a fixed `u32`, so a
+//! section can in principle grow past 4 GiB and small sections only cost a
+//! single byte.
+
+use std::fmt;
+
+/// A structured error from [`Decodable::decode`], distinct from the
+/// `anyhow::Error` the rest of this crate uses, so a caller can match on
+/// *why* a combined ciphertext failed to parse rather than just its message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    /// Ran out of bytes while reading a fixed-size field or a VarInt.
+    Truncated,
+    /// The leading magic tag didn't match [`COMBINED_CIPHERTEXT_MAGIC`].
+    BadMagic,
+    /// The format version byte isn't one this codec knows how to read.
+    UnsupportedFormatVersion(u8),
+    /// A VarInt used a wider encoding than its value required (e.g. `0xFD`
+    /// followed by a u16 that fits in a single byte).
+    NonCanonicalVarInt,
+    /// A section's VarInt length claims more bytes than remain in the
+    /// buffer.
+    SectionTooLong,
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::Truncated => write!(f, "truncated: ran out of bytes mid-field"),
+            CodecError::BadMagic => write!(f, "not a zkenc combined ciphertext: bad magic bytes"),
+            CodecError::UnsupportedFormatVersion(v) => {
+                write!(f, "unsupported combined ciphertext format version: {}", v)
+            }
+            CodecError::NonCanonicalVarInt => {
+                write!(f, "VarInt was not encoded in its shortest form")
+            }
+            CodecError::SectionTooLong => {
+                write!(f, "section length runs past the end of the buffer")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+/// A type that knows how to append its own wire encoding to `out`.
+pub trait Encodable {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+/// A type that knows how to read itself back out of `data`, starting at
+/// `*offset`, advancing `*offset` past whatever it consumed.
+pub trait Decodable: Sized {
+    fn decode(data: &[u8], offset: &mut usize) -> Result<Self, CodecError>;
+}
+
+/// Bitcoin-style `CompactSize`: a single byte for values below `0xFD`,
+/// `0xFD` + little-endian `u16`, `0xFE` + little-endian `u32`, or `0xFF` +
+/// little-endian `u64` - always the shortest encoding for the value, which
+/// [`Decodable::decode`] enforces by rejecting a wider marker than the
+/// value needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VarInt(pub u64);
+
+impl Encodable for VarInt {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self.0 {
+            v if v < 0xFD => out.push(v as u8),
+            v if v <= 0xFFFF => {
+                out.push(0xFD);
+                out.extend_from_slice(&(v as u16).to_le_bytes());
+            }
+            v if v <= 0xFFFF_FFFF => {
+                out.push(0xFE);
+                out.extend_from_slice(&(v as u32).to_le_bytes());
+            }
+            v => {
+                out.push(0xFF);
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+    }
+}
+
+impl Decodable for VarInt {
+    fn decode(data: &[u8], offset: &mut usize) -> Result<Self, CodecError> {
+        let tag = *data.get(*offset).ok_or(CodecError::Truncated)?;
+        *offset += 1;
+
+        let value = match tag {
+            0xFD => {
+                let bytes = read_array::<2>(data, offset)?;
+                let v = u16::from_le_bytes(bytes) as u64;
+                if v < 0xFD {
+                    return Err(CodecError::NonCanonicalVarInt);
+                }
+                v
+            }
+            0xFE => {
+                let bytes = read_array::<4>(data, offset)?;
+                let v = u32::from_le_bytes(bytes) as u64;
+                if v <= 0xFFFF {
+                    return Err(CodecError::NonCanonicalVarInt);
+                }
+                v
+            }
+            0xFF => {
+                let bytes = read_array::<8>(data, offset)?;
+                let v = u64::from_le_bytes(bytes);
+                if v <= 0xFFFF_FFFF {
+                    return Err(CodecError::NonCanonicalVarInt);
+                }
+                v
+            }
+            small => small as u64,
+        };
+
+        Ok(VarInt(value))
+    }
+}
+
+/// Read a fixed-size little-endian field at `*offset`, advancing `*offset`
+/// past it, without panicking on a truncated buffer.
+fn read_array<const N: usize>(data: &[u8], offset: &mut usize) -> Result<[u8; N], CodecError> {
+    let end = offset.checked_add(N).ok_or(CodecError::Truncated)?;
+    let slice = data.get(*offset..end).ok_or(CodecError::Truncated)?;
+    *offset = end;
+    Ok(slice.try_into().expect("slice has exactly N bytes"))
+}
+
+/// Read a VarInt-prefixed byte section, advancing `*offset` past both the
+/// length and the section itself.
+fn read_section(data: &[u8], offset: &mut usize) -> Result<Vec<u8>, CodecError> {
+    let len = VarInt::decode(data, offset)?.0 as usize;
+    let end = offset.checked_add(len).ok_or(CodecError::SectionTooLong)?;
+    let section = data.get(*offset..end).ok_or(CodecError::SectionTooLong)?;
+    *offset = end;
+    Ok(section.to_vec())
+}
+
+fn write_section(out: &mut Vec<u8>, section: &[u8]) {
+    VarInt(section.len() as u64).encode(out);
+    out.extend_from_slice(section);
+}
+
+/// 4-byte magic identifying a [`CombinedCiphertext`]'s binary encoding.
+const COMBINED_CIPHERTEXT_MAGIC: &[u8; 4] = b"ZKCC";
+
+/// Wire format version. Bumped if the header or field layout changes
+/// incompatibly; unrelated to `version` below, which is this crate's own
+/// encryption-mode marker (legacy GCM / `--aead` / `--field`).
+const FORMAT_VERSION: u8 = 1;
+
+/// A parsed combined-ciphertext artifact - the format `encrypt_command`
+/// assembles and `decrypt_command` consumes.
+///
+/// `version`/`flag` are handed back uninterpreted: `version` is this
+/// crate's encryption-mode marker (0 = legacy GCM, 1 = `--aead`, 2 =
+/// `--field`) and `flag` records whether public inputs were embedded - it's
+/// up to the caller to decide what they mean for its own CLI flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CombinedCiphertext {
+    pub version: u8,
+    pub flag: u8,
+    pub witness_ct: Vec<u8>,
+    pub public_input: Option<Vec<u8>>,
+    pub encrypted_message: Vec<u8>,
+}
+
+impl Encodable for CombinedCiphertext {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(COMBINED_CIPHERTEXT_MAGIC);
+        out.push(FORMAT_VERSION);
+        out.push(self.version);
+        out.push(self.flag);
+        write_section(out, &self.witness_ct);
+        if let Some(public_input) = &self.public_input {
+            write_section(out, public_input);
+        }
+        out.extend_from_slice(&self.encrypted_message);
+    }
+}
+
+impl Decodable for CombinedCiphertext {
+    fn decode(data: &[u8], offset: &mut usize) -> Result<Self, CodecError> {
+        let magic = read_array::<4>(data, offset)?;
+        if &magic != COMBINED_CIPHERTEXT_MAGIC {
+            return Err(CodecError::BadMagic);
+        }
+
+        let format_version = read_array::<1>(data, offset)?[0];
+        if format_version != FORMAT_VERSION {
+            return Err(CodecError::UnsupportedFormatVersion(format_version));
+        }
+
+        let version = read_array::<1>(data, offset)?[0];
+        let flag = read_array::<1>(data, offset)?[0];
+
+        let witness_ct = read_section(data, offset)?;
+
+        let public_input = if flag == 1 {
+            Some(read_section(data, offset)?)
+        } else {
+            None
+        };
+
+        let encrypted_message = data
+            .get(*offset..)
+            .ok_or(CodecError::Truncated)?
+            .to_vec();
+        *offset = data.len();
+
+        Ok(CombinedCiphertext {
+            version,
+            flag,
+            witness_ct,
+            public_input,
+            encrypted_message,
+        })
+    }
+}
+
+impl CombinedCiphertext {
+    /// Encode to the full `[magic][format_version][version][flag]...` wire
+    /// format as a fresh `Vec<u8>`.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        Encodable::encode(self, &mut out);
+        out
+    }
+
+    /// Decode a full combined-ciphertext buffer (the counterpart to
+    /// `encode`), rejecting unknown magic/version or a truncated/malformed
+    /// section with a [`CodecError`] instead of panicking.
+    pub fn decode(data: &[u8]) -> Result<Self, CodecError> {
+        let mut offset = 0;
+        Decodable::decode(data, &mut offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint_bytes(value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        VarInt(value).encode(&mut out);
+        out
+    }
+
+    #[test]
+    fn test_varint_roundtrip_small() {
+        for value in [0u64, 1, 0xFC] {
+            let bytes = varint_bytes(value);
+            assert_eq!(bytes.len(), 1, "value {} should encode as a single byte", value);
+            let mut offset = 0;
+            assert_eq!(VarInt::decode(&bytes, &mut offset).unwrap(), VarInt(value));
+            assert_eq!(offset, bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_varint_roundtrip_u16_boundary() {
+        for value in [0xFDu64, 0x100, 0xFFFF] {
+            let bytes = varint_bytes(value);
+            assert_eq!(bytes[0], 0xFD);
+            assert_eq!(bytes.len(), 3);
+            let mut offset = 0;
+            assert_eq!(VarInt::decode(&bytes, &mut offset).unwrap(), VarInt(value));
+        }
+    }
+
+    #[test]
+    fn test_varint_roundtrip_u32_boundary() {
+        for value in [0x10000u64, 0xFFFF_FFFF] {
+            let bytes = varint_bytes(value);
+            assert_eq!(bytes[0], 0xFE);
+            assert_eq!(bytes.len(), 5);
+            let mut offset = 0;
+            assert_eq!(VarInt::decode(&bytes, &mut offset).unwrap(), VarInt(value));
+        }
+    }
+
+    #[test]
+    fn test_varint_roundtrip_u64_boundary() {
+        for value in [0x1_0000_0000u64, u64::MAX] {
+            let bytes = varint_bytes(value);
+            assert_eq!(bytes[0], 0xFF);
+            assert_eq!(bytes.len(), 9);
+            let mut offset = 0;
+            assert_eq!(VarInt::decode(&bytes, &mut offset).unwrap(), VarInt(value));
+        }
+    }
+
+    #[test]
+    fn test_varint_rejects_non_canonical_u16() {
+        // 0xFD marker with a value that fits in a single byte (0x00FC).
+        let bytes = vec![0xFD, 0xFC, 0x00];
+        let mut offset = 0;
+        assert_eq!(
+            VarInt::decode(&bytes, &mut offset),
+            Err(CodecError::NonCanonicalVarInt)
+        );
+    }
+
+    #[test]
+    fn test_varint_rejects_non_canonical_u32() {
+        // 0xFE marker with a value that fits in the u16 form.
+        let bytes = vec![0xFE, 0xFF, 0xFF, 0x00, 0x00];
+        let mut offset = 0;
+        assert_eq!(
+            VarInt::decode(&bytes, &mut offset),
+            Err(CodecError::NonCanonicalVarInt)
+        );
+    }
+
+    #[test]
+    fn test_varint_rejects_non_canonical_u64() {
+        // 0xFF marker with a value that fits in the u32 form.
+        let mut bytes = vec![0xFF];
+        bytes.extend_from_slice(&0xFFFF_FFFFu64.to_le_bytes());
+        let mut offset = 0;
+        assert_eq!(
+            VarInt::decode(&bytes, &mut offset),
+            Err(CodecError::NonCanonicalVarInt)
+        );
+    }
+
+    #[test]
+    fn test_varint_rejects_truncated_input() {
+        let mut offset = 0;
+        assert_eq!(VarInt::decode(&[], &mut offset), Err(CodecError::Truncated));
+
+        let mut offset = 0;
+        assert_eq!(
+            VarInt::decode(&[0xFD, 0x01], &mut offset),
+            Err(CodecError::Truncated)
+        );
+    }
+
+    fn sample(
+        version: u8,
+        witness_ct: &[u8],
+        public_input: Option<&[u8]>,
+        encrypted_message: &[u8],
+    ) -> CombinedCiphertext {
+        CombinedCiphertext {
+            version,
+            flag: if public_input.is_some() { 1 } else { 0 },
+            witness_ct: witness_ct.to_vec(),
+            public_input: public_input.map(|p| p.to_vec()),
+            encrypted_message: encrypted_message.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_combined_ciphertext_roundtrip_with_public_input() {
+        let original = sample(1, b"witness-ct", Some(b"public-input"), b"encrypted");
+        let bytes = original.encode();
+        let decoded = CombinedCiphertext::decode(&bytes).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_combined_ciphertext_roundtrip_without_public_input() {
+        let original = sample(0, b"witness-ct", None, b"encrypted");
+        let bytes = original.encode();
+        let decoded = CombinedCiphertext::decode(&bytes).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_combined_ciphertext_roundtrip_zero_length_sections() {
+        let original = sample(2, b"", Some(b""), b"");
+        let bytes = original.encode();
+        let decoded = CombinedCiphertext::decode(&bytes).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_combined_ciphertext_rejects_bad_magic() {
+        let mut bytes = sample(0, b"a", None, b"b").encode();
+        bytes[0] = b'X';
+        assert_eq!(
+            CombinedCiphertext::decode(&bytes),
+            Err(CodecError::BadMagic)
+        );
+    }
+
+    #[test]
+    fn test_combined_ciphertext_rejects_unsupported_format_version() {
+        let mut bytes = sample(0, b"a", None, b"b").encode();
+        bytes[4] = 0xFF;
+        assert_eq!(
+            CombinedCiphertext::decode(&bytes),
+            Err(CodecError::UnsupportedFormatVersion(0xFF))
+        );
+    }
+
+    #[test]
+    fn test_combined_ciphertext_rejects_empty_input() {
+        assert_eq!(CombinedCiphertext::decode(&[]), Err(CodecError::Truncated));
+    }
+
+    #[test]
+    fn test_combined_ciphertext_rejects_section_length_past_end() {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(COMBINED_CIPHERTEXT_MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.push(0); // version
+        bytes.push(0); // flag
+        bytes.push(0xFD); // claims a VarInt-u16 witness length...
+        bytes.extend_from_slice(&1000u16.to_le_bytes()); // ...far past the buffer
+        assert_eq!(
+            CombinedCiphertext::decode(&bytes),
+            Err(CodecError::SectionTooLong)
+        );
+    }
+
+    #[test]
+    fn test_combined_ciphertext_never_panics_on_arbitrary_bytes() {
+        let samples: [&[u8]; 6] = [
+            &[],
+            b"ZKCC",
+            b"ZKCC\x01",
+            b"ZKCC\x01\x00\x00",
+            b"ZKCC\x01\x00\x01\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF",
+            b"not zkcc at all",
+        ];
+        for sample in samples {
+            let _ = CombinedCiphertext::decode(sample);
+        }
+    }
+}
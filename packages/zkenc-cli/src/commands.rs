@@ -8,25 +8,51 @@
 
 use anyhow::{Context, Result};
 use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use std::collections::HashMap;
 use std::fs;
 
+use crate::circom;
 use crate::circuit::CircomCircuit;
+use crate::codec::CombinedCiphertext;
 use crate::crypto;
+use crate::error::ZkencError;
+use crate::formats::{self, OutputFormat};
+use crate::hybrid;
 use crate::r1cs::R1csFile;
+use crate::shamir::{self, Share};
 use crate::witness::WitnessFile;
-use zkenc_core::{decap, encap, Ciphertext, Key};
+use std::str::FromStr;
+use zkenc_core::{decap, encap, poseidon, Ciphertext, Key};
 
 /// Encap command: Generate ciphertext and key from circuit + public inputs
+///
+/// If `hybrid_pubkey_path` is set, the ciphertext and key are produced by
+/// [`crate::hybrid::encap_hybrid`] instead: an X25519 ephemeral-static
+/// exchange with the recipient public key at that path (32 raw bytes) is
+/// combined with the witness-KEM output, so the symmetric key stays secure
+/// even if the pairing assumptions behind the witness-KEM are broken.
+///
+/// If `armor` is set, the ciphertext and key files are written as
+/// OpenPGP-style ASCII-armored text (see [`crate::crypto::armor`]) instead
+/// of raw bytes, so they survive copy/paste or email transport.
+///
+/// `format` selects how the ciphertext and key bytes themselves are
+/// encoded before that optional armoring - see [`crate::formats`] for the
+/// `binary`/`hex`/`json` choices.
 pub fn encap_command(
     circuit_path: &str,
     input_path: &str,
     ciphertext_path: &str,
     key_path: &str,
-) -> Result<()> {
+    hybrid_pubkey_path: Option<&str>,
+    armor: bool,
+    format: OutputFormat,
+) -> Result<(), ZkencError> {
     println!("📂 Loading R1CS circuit...");
-    let r1cs = R1csFile::from_file(circuit_path).context("Failed to load R1CS circuit")?;
+    let r1cs = R1csFile::from_file(circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
 
     println!("   - Constraints: {}", r1cs.n_constraints);
     println!("   - Public inputs: {}", r1cs.n_pub_in);
@@ -34,8 +60,9 @@ pub fn encap_command(
 
     // Parse public inputs from JSON
     println!("\n📋 Loading public inputs from JSON...");
-    let input_json = fs::read_to_string(input_path).context("Failed to read input JSON file")?;
-    let inputs = parse_circuit_inputs(&input_json).context("Failed to parse input JSON")?;
+    let input_json = fs::read_to_string(input_path)
+        .map_err(|e| ZkencError::InputParse(format!("Failed to read input JSON file: {}", e)))?;
+    let inputs = parse_circuit_inputs(&input_json)?;
 
     println!("   - Parsed {} field elements", inputs.len());
 
@@ -48,96 +75,207 @@ pub fn encap_command(
     }
 
     println!("\n🔐 Running Encap...");
-    let mut circuit =
-        CircomCircuit::from_r1cs(&circuit_path).context("Failed to create circuit")?;
+    let mut circuit = CircomCircuit::<Fr>::from_r1cs(&circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
     circuit.set_witness(witness);
 
     // Setup RNG
     let mut rng = ark_std::rand::rngs::OsRng;
 
+    if let Some(pubkey_path) = hybrid_pubkey_path {
+        let recipient_public_key = read_x25519_public_key(pubkey_path)
+            .map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
+        let (ciphertext, key) = hybrid::encap_hybrid(circuit, &recipient_public_key, &mut rng)
+            .map_err(|e| ZkencError::EncapFailed(format!("Hybrid encap failed: {:#}", e)))?;
+
+        println!("\n💾 Saving hybrid ciphertext...");
+        let ciphertext_bytes = ciphertext
+            .to_bytes()
+            .map_err(|e| ZkencError::Crypto(format!("Failed to serialize hybrid ciphertext: {:#}", e)))?;
+        let ciphertext_bytes = formats::encode_ciphertext_bytes(ciphertext_bytes, format)
+            .map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
+        write_artifact(ciphertext_path, &ciphertext_bytes, crypto::ArmorType::Ciphertext, armor)
+            .map_err(|e| ZkencError::Crypto(format!("Failed to write ciphertext file: {:#}", e)))?;
+        println!("   ✅ Ciphertext saved ({} bytes)", ciphertext_bytes.len());
+
+        println!("\n🔑 Saving key...");
+        let key_bytes = formats::encode_key(&key, format).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
+        write_artifact(key_path, &key_bytes, crypto::ArmorType::Key, armor)
+            .map_err(|e| ZkencError::Crypto(format!("Failed to write key file: {:#}", e)))?;
+        println!("   ✅ Key saved ({} bytes)", key_bytes.len());
+
+        return Ok(());
+    }
+
     // Call zkenc-core encap
-    let (ciphertext, key) = encap::<Bn254, _, _>(circuit, &mut rng)
-        .map_err(|e| anyhow::anyhow!("Encap failed: {:?}", e))?;
+    let (ciphertext, key) =
+        encap::<Bn254, _, _>(circuit, &mut rng).map_err(|e| ZkencError::EncapFailed(format!("{:?}", e)))?;
 
     // Serialize and save ciphertext
     println!("\n💾 Saving ciphertext...");
-    let mut ciphertext_bytes = Vec::new();
-    ciphertext
-        .serialize_compressed(&mut ciphertext_bytes)
-        .context("Failed to serialize ciphertext")?;
+    let ciphertext_bytes =
+        formats::encode_ciphertext(&ciphertext, format).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
 
-    fs::write(ciphertext_path, &ciphertext_bytes).context("Failed to write ciphertext file")?;
+    write_artifact(ciphertext_path, &ciphertext_bytes, crypto::ArmorType::Ciphertext, armor)
+        .map_err(|e| ZkencError::Crypto(format!("Failed to write ciphertext file: {:#}", e)))?;
     println!("   ✅ Ciphertext saved ({} bytes)", ciphertext_bytes.len());
 
     // Serialize and save key
     println!("\n🔑 Saving key...");
-    let mut key_bytes = Vec::new();
-    key.serialize_compressed(&mut key_bytes)
-        .context("Failed to serialize key")?;
+    let key_bytes = formats::encode_key(&key, format).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
 
-    fs::write(key_path, &key_bytes).context("Failed to write key file")?;
+    write_artifact(key_path, &key_bytes, crypto::ArmorType::Key, armor)
+        .map_err(|e| ZkencError::Crypto(format!("Failed to write key file: {:#}", e)))?;
     println!("   ✅ Key saved ({} bytes)", key_bytes.len());
 
     Ok(())
 }
 
+/// Write `bytes` to `path`, ASCII-armoring them first (see
+/// [`crate::crypto::armor`]) when `armor` is set. Every artifact this CLI
+/// produces is assumed to be over BN254, the only curve `encap`/`decap`
+/// currently run against.
+fn write_artifact(path: &str, bytes: &[u8], armor_type: crypto::ArmorType, armor: bool) -> Result<()> {
+    if armor {
+        let armored = crypto::armor(bytes, armor_type, crypto::CurveId::Bn254);
+        fs::write(path, armored)?;
+    } else {
+        fs::write(path, bytes)?;
+    }
+    Ok(())
+}
+
+/// Read `path`'s contents, transparently dearmoring them (see
+/// [`crate::crypto::dearmor_expect`]) if they look like an ASCII-armored
+/// block, and rejecting a block whose declared type or curve doesn't match
+/// `expected_type`/BN254. Falls back to the raw bytes when the file isn't
+/// armored at all.
+fn read_artifact(path: &str, expected_type: crypto::ArmorType) -> Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    if crypto::looks_armored(&bytes) {
+        let text = String::from_utf8(bytes).context("Armored file is not valid UTF-8 text")?;
+        crypto::dearmor_expect(&text, expected_type, crypto::CurveId::Bn254)
+    } else {
+        Ok(bytes)
+    }
+}
+
 /// Decap command: Recover key from circuit + witness + ciphertext
+///
+/// If `hybrid_secret_path` is set, the ciphertext is parsed as a
+/// [`crate::hybrid::HybridCiphertext`] and recovered via
+/// [`crate::hybrid::decap_hybrid`] using the X25519 secret key at that path
+/// (32 raw bytes) - both the witness and that secret key must be correct.
+///
+/// `format` must match whichever encoding the ciphertext file was written
+/// in (see [`crate::formats`]); the recovered key is written back out in
+/// that same encoding.
 pub fn decap_command(
     circuit_path: &str,
-    witness_path: &str,
+    witness_path: Option<&str>,
+    wasm_path: Option<&str>,
+    input_path: Option<&str>,
     ciphertext_path: &str,
     key_path: &str,
-) -> Result<()> {
-    println!("📂 Loading R1CS circuit...");
-    let r1cs = R1csFile::from_file(circuit_path).context("Failed to load R1CS circuit")?;
-
-    println!("   - Constraints: {}", r1cs.n_constraints);
-    println!("   - Public inputs: {}", r1cs.n_pub_in);
-    println!("   - Wires: {}", r1cs.n_wires);
-
-    // Load witness from snarkjs .wtns file
-    println!("\n📋 Loading witness from snarkjs...");
-    let witness_file =
-        WitnessFile::from_file(witness_path).context("Failed to load witness file")?;
+    hybrid_secret_path: Option<&str>,
+    format: OutputFormat,
+) -> Result<(), ZkencError> {
+    println!("📂 Loading R1CS circuit and witness...");
+    let circuit = match (witness_path, wasm_path, input_path) {
+        (Some(witness_path), None, None) => {
+            CircomCircuit::<Fr>::from_r1cs_and_witness(circuit_path, witness_path).map_err(|e| {
+                ZkencError::WitnessLoad(format!("Failed to load circuit and witness: {:#}", e))
+            })?
+        }
+        (None, Some(wasm_path), Some(input_path)) => {
+            // Confirms the R1CS and the paired wasm witness generator are
+            // both readable before spending time computing a witness.
+            circom::load_circom_circuit(circuit_path, wasm_path)
+                .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
+            let inputs = circom::parse_inputs(input_path)
+                .map_err(|e| ZkencError::InputParse(format!("{:#}", e)))?;
+            println!("   Computing witness from wasm witness generator...");
+            CircomCircuit::<Fr>::from_r1cs_and_wasm(circuit_path, wasm_path, &inputs).map_err(|e| {
+                ZkencError::WitnessLoad(format!("Failed to compute witness from wasm: {:#}", e))
+            })?
+        }
+        _ => {
+            return Err(ZkencError::WitnessLoad(
+                "Decap requires either --witness, or both --wasm and --input".to_string(),
+            ))
+        }
+    };
 
-    println!("   - Witness elements: {}", witness_file.n_witness);
+    println!("   - Constraints: {}", circuit.n_constraints());
+    println!("   - Public inputs: {}", circuit.n_public_inputs());
 
-    // Convert witness to field elements
-    let witness = witness_file
-        .to_field_elements::<Fr>()
-        .context("Failed to convert witness to field elements")?;
-
-    // Load ciphertext
+    // Load ciphertext (transparently dearmored if it's an ASCII-armored block)
     println!("\n📦 Loading ciphertext...");
-    let ciphertext_bytes = fs::read(ciphertext_path).context("Failed to read ciphertext file")?;
-
-    let ciphertext = Ciphertext::<Bn254>::deserialize_compressed(&ciphertext_bytes[..])
-        .context("Failed to deserialize ciphertext")?;
-
+    let ciphertext_bytes = read_artifact(ciphertext_path, crypto::ArmorType::Ciphertext).map_err(|e| {
+        ZkencError::CiphertextDecode {
+            expected: "a readable ciphertext file".to_string(),
+            got: format!("{:#}", e),
+        }
+    })?;
     println!("   - Ciphertext size: {} bytes", ciphertext_bytes.len());
 
-    // Create circuit with full witness
     println!("\n🔓 Running Decap...");
-    let mut circuit =
-        CircomCircuit::from_r1cs(&circuit_path).context("Failed to create circuit")?;
-    circuit.set_witness(witness);
 
-    // Call zkenc-core decap
-    let key = decap::<Bn254, _>(circuit, &ciphertext)
-        .map_err(|e| anyhow::anyhow!("Decap failed: {:?}", e))?;
+    let key = if let Some(secret_path) = hybrid_secret_path {
+        let recipient_secret_key =
+            read_x25519_secret_key(secret_path).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
+        let ciphertext_bytes = formats::decode_ciphertext_bytes(&ciphertext_bytes, format).map_err(|e| {
+            ZkencError::CiphertextDecode {
+                expected: "a ciphertext encoded in the requested format".to_string(),
+                got: format!("{:#}", e),
+            }
+        })?;
+        let ciphertext = hybrid::HybridCiphertext::from_bytes(&ciphertext_bytes).map_err(|e| {
+            ZkencError::CiphertextDecode {
+                expected: "a hybrid ciphertext".to_string(),
+                got: format!("{:#}", e),
+            }
+        })?;
+        hybrid::decap_hybrid(circuit, &ciphertext, &recipient_secret_key)
+            .map_err(|e| ZkencError::DecapFailed(format!("Hybrid decap failed: {:#}", e)))?
+    } else {
+        let ciphertext = formats::decode_ciphertext(&ciphertext_bytes, format).map_err(|e| {
+            ZkencError::CiphertextDecode {
+                expected: "a witness ciphertext".to_string(),
+                got: format!("{:#}", e),
+            }
+        })?;
+        decap::<Bn254, _>(circuit, &ciphertext).map_err(|e| ZkencError::DecapFailed(format!("{:?}", e)))?
+    };
 
     // Serialize and save key
     println!("\n🔑 Saving recovered key...");
-    let mut key_bytes = Vec::new();
-    key.serialize_compressed(&mut key_bytes)
-        .context("Failed to serialize key")?;
+    let key_bytes = formats::encode_key(&key, format).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
 
-    fs::write(key_path, &key_bytes).context("Failed to write key file")?;
+    fs::write(key_path, &key_bytes).map_err(|e| ZkencError::Crypto(format!("Failed to write key file: {}", e)))?;
     println!("   ✅ Key saved ({} bytes)", key_bytes.len());
 
     Ok(())
 }
 
+/// Read a raw 32-byte X25519 public key from `path`.
+fn read_x25519_public_key(path: &str) -> Result<x25519_dalek::PublicKey> {
+    let bytes = fs::read(path).context("Failed to read X25519 public key file")?;
+    let array: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("X25519 public key file must be exactly 32 bytes"))?;
+    Ok(x25519_dalek::PublicKey::from(array))
+}
+
+/// Read a raw 32-byte X25519 secret key from `path`.
+fn read_x25519_secret_key(path: &str) -> Result<x25519_dalek::StaticSecret> {
+    let bytes = fs::read(path).context("Failed to read X25519 secret key file")?;
+    let array: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("X25519 secret key file must be exactly 32 bytes"))?;
+    Ok(x25519_dalek::StaticSecret::from(array))
+}
+
 /// Encrypt command: High-level encryption with combined ciphertext format
 /// This format is compatible with zkenc-js encrypt() function
 pub fn encrypt_command(
@@ -146,11 +284,21 @@ pub fn encrypt_command(
     message_path: &str,
     output_path: &str,
     include_public_input: bool,
-) -> Result<()> {
+    aead: bool,
+    field: bool,
+    armor: bool,
+    format: OutputFormat,
+) -> Result<(), ZkencError> {
+    if aead && field {
+        return Err(ZkencError::Crypto(
+            "--aead and --field are different encryption backends and cannot both be set".to_string(),
+        ));
+    }
     // Step 1: Run encap to get witness ciphertext and key
     println!("🔐 Step 1: Running Encap...");
     println!("� Loading R1CS circuit...");
-    let r1cs = R1csFile::from_file(circuit_path).context("Failed to load R1CS circuit")?;
+    let r1cs = R1csFile::from_file(circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
 
     println!("   - Constraints: {}", r1cs.n_constraints);
     println!("   - Public inputs: {}", r1cs.n_pub_in);
@@ -158,8 +306,9 @@ pub fn encrypt_command(
 
     // Parse public inputs from JSON
     println!("\n📋 Loading public inputs from JSON...");
-    let input_json = fs::read_to_string(input_path).context("Failed to read input JSON file")?;
-    let inputs = parse_circuit_inputs(&input_json).context("Failed to parse input JSON")?;
+    let input_json = fs::read_to_string(input_path)
+        .map_err(|e| ZkencError::InputParse(format!("Failed to read input JSON file: {}", e)))?;
+    let inputs = parse_circuit_inputs(&input_json)?;
 
     println!("   - Parsed {} field elements", inputs.len());
 
@@ -171,22 +320,22 @@ pub fn encrypt_command(
         witness.insert((i + 1) as u32, *value);
     }
 
-    let mut circuit =
-        CircomCircuit::from_r1cs(&circuit_path).context("Failed to create circuit")?;
+    let mut circuit = CircomCircuit::<Fr>::from_r1cs(&circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
     circuit.set_witness(witness);
 
     // Setup RNG
     let mut rng = ark_std::rand::rngs::OsRng;
 
     // Call zkenc-core encap
-    let (ciphertext, key) = encap::<Bn254, _, _>(circuit, &mut rng)
-        .map_err(|e| anyhow::anyhow!("Encap failed: {:?}", e))?;
+    let (ciphertext, key) =
+        encap::<Bn254, _, _>(circuit, &mut rng).map_err(|e| ZkencError::EncapFailed(format!("{:?}", e)))?;
 
     // Serialize witness ciphertext
     let mut witness_ct_bytes = Vec::new();
     ciphertext
         .serialize_compressed(&mut witness_ct_bytes)
-        .context("Failed to serialize ciphertext")?;
+        .map_err(|e| ZkencError::Crypto(format!("Failed to serialize ciphertext: {:#}", e)))?;
 
     println!(
         "   ✅ Witness ciphertext generated ({} bytes)",
@@ -195,11 +344,50 @@ pub fn encrypt_command(
 
     // Step 2: Encrypt message with key
     println!("\n� Step 2: Encrypting message...");
-    let message = fs::read(message_path).context("Failed to read message file")?;
-    println!("   - Message size: {} bytes", message.len());
 
-    let encrypted_message =
-        crypto::encrypt_gcm(key.as_bytes(), &message).context("Message encryption failed")?;
+    let encrypted_message = if field {
+        // Field mode keeps the message inside the curve's scalar field
+        // (as a JSON array of decimal/numeric field elements) instead of
+        // treating it as opaque bytes, via the Poseidon duplex cipher.
+        let message_json = fs::read_to_string(message_path).map_err(|e| {
+            ZkencError::InputParse(format!(
+                "Failed to read message file (expected a JSON array for --field): {}",
+                e
+            ))
+        })?;
+        let plaintext = parse_field_message(&message_json)
+            .map_err(|e| ZkencError::InputParse(format!("Failed to parse field message: {:#}", e)))?;
+        println!("   - Message: {} field elements", plaintext.len());
+
+        let field_ciphertext = poseidon::encrypt_field(&key, &plaintext, &mut rng);
+        let mut bytes = Vec::new();
+        field_ciphertext
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| ZkencError::Crypto(format!("Failed to serialize field ciphertext: {:#}", e)))?;
+        bytes
+    } else {
+        let message = fs::read(message_path)
+            .map_err(|e| ZkencError::InputParse(format!("Failed to read message file: {}", e)))?;
+        println!("   - Message size: {} bytes", message.len());
+
+        if aead {
+            // Bind the ciphertext's public inputs as GCM associated data, so
+            // tampering with them (or decrypting against a mismatched puzzle)
+            // fails the authentication tag instead of silently decrypting.
+            let mut public_inputs_bytes = Vec::new();
+            ciphertext
+                .public_inputs
+                .serialize_compressed(&mut public_inputs_bytes)
+                .map_err(|e| {
+                    ZkencError::Crypto(format!("Failed to serialize public inputs for AEAD associated data: {:#}", e))
+                })?;
+            crypto::encrypt_gcm_with_aad(key.as_bytes(), &message, &public_inputs_bytes)
+                .map_err(|e| ZkencError::Crypto(format!("Message encryption failed: {:#}", e)))?
+        } else {
+            crypto::encrypt_gcm(key.as_bytes(), &message)
+                .map_err(|e| ZkencError::Crypto(format!("Message encryption failed: {:#}", e)))?
+        }
+    };
     println!("   ✅ Message encrypted ({} bytes)", encrypted_message.len());
 
     // Step 3: Combine into zkenc-js compatible format
@@ -212,37 +400,30 @@ pub fn encrypt_command(
         Vec::new()
     };
 
-    // Calculate total size
-    // Format: [flag(1)][witnessLen(4)][witnessCT][publicLen(4)?][publicInput?][encryptedMsg]
+    // version 0 is the original GCM-without-AAD layout; version 1 marks the
+    // message as sealed with `--aead` (public inputs bound as GCM
+    // associated data, nonce carried inside `encryptedMsg` as before);
+    // version 2 marks `--field` mode, where `encryptedMsg` is a
+    // `CanonicalSerialize`d `[nonce, ciphertext.., tag]` vector of scalar
+    // field elements produced by the Poseidon duplex cipher instead of an
+    // AES-GCM byte stream. The wire layout itself (magic, format version,
+    // VarInt-prefixed sections) is handled by `codec::CombinedCiphertext`.
+    let version: u8 = if field { 2 } else if aead { 1 } else { 0 };
     let flag: u8 = if include_public_input { 1 } else { 0 };
-    let header_size = if include_public_input { 9 } else { 5 };
-    let total_size = header_size
-        + witness_ct_bytes.len()
-        + public_input_bytes.len()
-        + encrypted_message.len();
-
-    let mut combined = Vec::with_capacity(total_size);
-
-    // Write flag
-    combined.push(flag);
-
-    // Write witness ciphertext length (big-endian u32)
-    combined.extend_from_slice(&(witness_ct_bytes.len() as u32).to_be_bytes());
 
-    // Write witness ciphertext
-    combined.extend_from_slice(&witness_ct_bytes);
-
-    // Write public input if included
-    if include_public_input {
-        combined.extend_from_slice(&(public_input_bytes.len() as u32).to_be_bytes());
-        combined.extend_from_slice(&public_input_bytes);
+    let combined = CombinedCiphertext {
+        version,
+        flag,
+        witness_ct: witness_ct_bytes,
+        public_input: if include_public_input { Some(public_input_bytes) } else { None },
+        encrypted_message,
     }
-
-    // Write encrypted message
-    combined.extend_from_slice(&encrypted_message);
+    .encode();
+    let combined = formats::encode_ciphertext_bytes(combined, format).map_err(|e| ZkencError::Crypto(format!("{:#}", e)))?;
 
     // Save combined ciphertext
-    fs::write(output_path, &combined).context("Failed to write combined ciphertext")?;
+    write_artifact(output_path, &combined, crypto::ArmorType::CombinedMessage, armor)
+        .map_err(|e| ZkencError::Crypto(format!("Failed to write combined ciphertext: {:#}", e)))?;
     println!("   ✅ Combined ciphertext saved ({} bytes)", combined.len());
 
     if include_public_input {
@@ -261,69 +442,69 @@ pub fn decrypt_command(
     witness_path: &str,
     ciphertext_path: &str,
     output_path: &str,
-) -> Result<()> {
-    // Step 1: Parse combined ciphertext
-    println!("� Step 1: Parsing combined ciphertext...");
-    let combined = fs::read(ciphertext_path).context("Failed to read ciphertext file")?;
-
-    if combined.len() < 5 {
-        anyhow::bail!("Invalid ciphertext: too short");
-    }
-
-    let mut offset = 0;
-
-    // Read flag
-    let flag = combined[offset];
-    offset += 1;
-
-    // Read witness ciphertext length
-    let witness_len = u32::from_be_bytes([
-        combined[offset],
-        combined[offset + 1],
-        combined[offset + 2],
-        combined[offset + 3],
-    ]) as usize;
-    offset += 4;
-
-    if combined.len() < offset + witness_len {
-        anyhow::bail!("Invalid ciphertext: witness length mismatch");
+    aead: bool,
+    field: bool,
+    format: OutputFormat,
+) -> Result<(), ZkencError> {
+    if aead && field {
+        return Err(ZkencError::Crypto(
+            "--aead and --field are different encryption backends and cannot both be set".to_string(),
+        ));
     }
-
-    // Extract witness ciphertext
-    let witness_ct_bytes = &combined[offset..offset + witness_len];
-    offset += witness_len;
-
-    println!("   - Flag: {}", flag);
-    println!("   - Witness ciphertext: {} bytes", witness_len);
-
-    // Skip public input if present (flag === 1)
-    if flag == 1 {
-        if combined.len() < offset + 4 {
-            anyhow::bail!("Invalid ciphertext: missing public input length");
+    // Step 1: Parse combined ciphertext (transparently dearmored if it's an
+    // ASCII-armored block)
+    println!("� Step 1: Parsing combined ciphertext...");
+    let combined = read_artifact(ciphertext_path, crypto::ArmorType::CombinedMessage).map_err(|e| {
+        ZkencError::CiphertextDecode {
+            expected: "a readable ciphertext file".to_string(),
+            got: format!("{:#}", e),
         }
-
-        let public_len = u32::from_be_bytes([
-            combined[offset],
-            combined[offset + 1],
-            combined[offset + 2],
-            combined[offset + 3],
-        ]) as usize;
-        offset += 4;
-
-        if combined.len() < offset + public_len {
-            anyhow::bail!("Invalid ciphertext: public input length mismatch");
+    })?;
+    let combined = formats::decode_ciphertext_bytes(&combined, format).map_err(|e| ZkencError::CiphertextDecode {
+        expected: "a ciphertext encoded in the requested format".to_string(),
+        got: format!("{:#}", e),
+    })?;
+
+    let parsed = CombinedCiphertext::decode(&combined).map_err(|e| ZkencError::CiphertextDecode {
+        expected: "a combined ciphertext".to_string(),
+        got: format!("{:#}", e),
+    })?;
+
+    let (ciphertext_is_aead, ciphertext_is_field) = match parsed.version {
+        0 => (false, false),
+        1 => (true, false),
+        2 => (false, true),
+        other => {
+            return Err(ZkencError::CiphertextDecode {
+                expected: "a combined ciphertext version of 0, 1, or 2".to_string(),
+                got: format!("version {}", other),
+            })
         }
+    };
+    if ciphertext_is_aead != aead {
+        return Err(ZkencError::CiphertextDecode {
+            expected: format!("--aead={}", ciphertext_is_aead),
+            got: format!("--aead={} was requested", aead),
+        });
+    }
+    if ciphertext_is_field != field {
+        return Err(ZkencError::CiphertextDecode {
+            expected: format!("--field={}", ciphertext_is_field),
+            got: format!("--field={} was requested", field),
+        });
+    }
 
-        // Extract and display public input
-        let public_input = &combined[offset..offset + public_len];
-        let public_str = String::from_utf8_lossy(public_input);
-        println!("   - Public input: {}", public_str);
-
-        offset += public_len;
+    println!("   - Flag: {}", parsed.flag);
+    println!("   - Witness ciphertext: {} bytes", parsed.witness_ct.len());
+    if let Some(public_input) = &parsed.public_input {
+        println!(
+            "   - Public input: {}",
+            String::from_utf8_lossy(public_input)
+        );
     }
 
-    // Extract encrypted message
-    let encrypted_message = &combined[offset..];
+    let witness_ct_bytes = &parsed.witness_ct[..];
+    let encrypted_message = &parsed.encrypted_message[..];
     println!(
         "   - Encrypted message: {} bytes",
         encrypted_message.len()
@@ -332,45 +513,72 @@ pub fn decrypt_command(
     // Step 2: Load circuit and witness
     println!("\n🔓 Step 2: Running Decap...");
     println!("📂 Loading R1CS circuit...");
-    let r1cs = R1csFile::from_file(circuit_path).context("Failed to load R1CS circuit")?;
+    let r1cs = R1csFile::from_file(circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
 
     println!("   - Constraints: {}", r1cs.n_constraints);
     println!("   - Public inputs: {}", r1cs.n_pub_in);
 
     // Load witness from snarkjs .wtns file
     println!("\n📋 Loading witness from snarkjs...");
-    let witness_file =
-        WitnessFile::from_file(witness_path).context("Failed to load witness file")?;
+    let witness_file = WitnessFile::from_file(witness_path)
+        .map_err(|e| ZkencError::WitnessLoad(format!("{:#}", e)))?;
 
     println!("   - Witness elements: {}", witness_file.n_witness);
 
     // Convert witness to field elements
     let witness = witness_file
         .to_field_elements::<Fr>()
-        .context("Failed to convert witness to field elements")?;
+        .map_err(|e| ZkencError::WitnessLoad(format!("Failed to convert witness to field elements: {:#}", e)))?;
 
     // Deserialize witness ciphertext
-    let witness_ciphertext = Ciphertext::<Bn254>::deserialize_compressed(&witness_ct_bytes[..])
-        .context("Failed to deserialize witness ciphertext")?;
+    let witness_ciphertext =
+        Ciphertext::<Bn254>::deserialize_compressed(&witness_ct_bytes[..]).map_err(|e| {
+            ZkencError::CiphertextDecode {
+                expected: "a witness ciphertext".to_string(),
+                got: format!("{:#}", e),
+            }
+        })?;
 
     // Create circuit with full witness
-    let mut circuit =
-        CircomCircuit::from_r1cs(&circuit_path).context("Failed to create circuit")?;
+    let mut circuit = CircomCircuit::<Fr>::from_r1cs(&circuit_path)
+        .map_err(|e| ZkencError::CircuitLoad(format!("{:#}", e)))?;
     circuit.set_witness(witness);
 
     // Call zkenc-core decap to recover key
     let key = decap::<Bn254, _>(circuit, &witness_ciphertext)
-        .map_err(|e| anyhow::anyhow!("Decap failed: {:?}", e))?;
+        .map_err(|e| ZkencError::DecapFailed(format!("{:?}", e)))?;
 
     println!("   ✅ Key recovered from witness");
 
     // Step 3: Decrypt message with recovered key
     println!("\n🔓 Step 3: Decrypting message...");
-    let plaintext = crypto::decrypt_gcm(key.as_bytes(), encrypted_message)
-        .context("Message decryption failed")?;
+    let plaintext = if field {
+        let field_ciphertext: Vec<Fr> = poseidon::deserialize_field_ciphertext(encrypted_message)
+            .map_err(|e| ZkencError::CiphertextDecode {
+                expected: "a field ciphertext".to_string(),
+                got: format!("{:?}", e),
+            })?;
+        let plaintext_fields = poseidon::decrypt_field(&key, &field_ciphertext)
+            .map_err(|e| ZkencError::Crypto(format!("Message decryption failed: {:?}", e)))?;
+        field_elements_to_json(&plaintext_fields).into_bytes()
+    } else if aead {
+        let mut public_inputs_bytes = Vec::new();
+        witness_ciphertext
+            .public_inputs
+            .serialize_compressed(&mut public_inputs_bytes)
+            .map_err(|e| {
+                ZkencError::Crypto(format!("Failed to serialize public inputs for AEAD associated data: {:#}", e))
+            })?;
+        crypto::decrypt_gcm_with_aad(key.as_bytes(), encrypted_message, &public_inputs_bytes)
+            .map_err(|e| ZkencError::Crypto(format!("Message decryption failed: {:#}", e)))?
+    } else {
+        crypto::decrypt_gcm(key.as_bytes(), encrypted_message)
+            .map_err(|e| ZkencError::Crypto(format!("Message decryption failed: {:#}", e)))?
+    };
 
     // Save decrypted message
-    fs::write(output_path, &plaintext).context("Failed to write decrypted file")?;
+    fs::write(output_path, &plaintext).map_err(|e| ZkencError::Crypto(format!("Failed to write decrypted file: {}", e)))?;
     println!("   ✅ Decrypted message saved ({} bytes)", plaintext.len());
 
     println!("\n✨ Decryption complete!");
@@ -378,41 +586,137 @@ pub fn decrypt_command(
     Ok(())
 }
 
+/// Share-key command: split a `key_encap.bin`/`key_decap.bin` file into `n`
+/// Shamir shares, any `t` of which later reconstruct it via `combine-key`.
+///
+/// Writes one file per share to `output_dir`, named `share-{x}.bin`.
+pub fn share_key_command(
+    key_path: &str,
+    threshold: usize,
+    shares: usize,
+    output_dir: &str,
+) -> Result<()> {
+    println!("🔑 Loading key...");
+    let key_bytes = fs::read(key_path).context("Failed to read key file")?;
+    let key = Key::deserialize_compressed(&key_bytes[..]).context("Failed to deserialize key")?;
+
+    println!(
+        "✂️  Splitting key into {} shares (threshold {})...",
+        shares, threshold
+    );
+    let mut rng = ark_std::rand::rngs::OsRng;
+    let generated = shamir::split_key(&key, threshold, shares, &mut rng)
+        .context("Failed to split key into shares")?;
+
+    fs::create_dir_all(output_dir).context("Failed to create output directory")?;
+    for share in &generated {
+        let mut share_bytes = Vec::new();
+        share
+            .serialize_compressed(&mut share_bytes)
+            .context("Failed to serialize share")?;
+
+        let share_path = format!("{}/share-{}.bin", output_dir, share.x);
+        fs::write(&share_path, &share_bytes).context("Failed to write share file")?;
+        println!("   - Wrote {}", share_path);
+    }
+
+    println!("\n✨ Sharing complete! Any {} of these {} shares reconstruct the key.", threshold, shares);
+    Ok(())
+}
+
+/// Combine-key command: reconstruct a key from `t` Shamir shares produced by
+/// `share-key`.
+pub fn combine_key_command(share_paths: &[String], threshold: usize, output_path: &str) -> Result<()> {
+    println!("📦 Loading {} shares...", share_paths.len());
+    let mut shares: Vec<Share> = Vec::with_capacity(share_paths.len());
+    for path in share_paths {
+        let bytes = fs::read(path).with_context(|| format!("Failed to read share file: {}", path))?;
+        let share = Share::deserialize_compressed(&bytes[..])
+            .with_context(|| format!("Failed to deserialize share file: {}", path))?;
+        shares.push(share);
+    }
+
+    println!("🧩 Reconstructing key (threshold {})...", threshold);
+    let key = shamir::combine_shares(&shares, threshold).context("Failed to reconstruct key")?;
+
+    let mut key_bytes = Vec::new();
+    key.serialize_compressed(&mut key_bytes)
+        .context("Failed to serialize reconstructed key")?;
+    fs::write(output_path, &key_bytes).context("Failed to write reconstructed key file")?;
+
+    println!("   ✅ Reconstructed key saved to {}", output_path);
+    Ok(())
+}
+
+/// Parse a decimal or `0x`-prefixed hex string into a field element,
+/// honoring an optional leading `-` (meaning `p - x` in the field).
+///
+/// Circom/snarkjs field elements and hashes occupy up to ~254 bits, well
+/// past `u64`, and are conventionally passed as arbitrary-precision
+/// decimal or hex string literals - this is what lets `parse_circuit_inputs`
+/// handle real witness/input files instead of only toy values that happen
+/// to fit in a machine word. Unlike [`parse_field_message`]'s hex
+/// convention (little-endian canonical serialization bytes), a hex string
+/// here is read the way hex numbers normally are: most-significant digit
+/// first, via `from_be_bytes_mod_order`.
+fn parse_signed_field_element(s: &str) -> Result<Fr, ZkencError> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+
+    let magnitude = if let Some(hex_digits) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        let bytes = hex::decode(hex_digits)
+            .map_err(|e| ZkencError::InputParse(format!("Invalid hex field element '{}': {}", s, e)))?;
+        Fr::from_be_bytes_mod_order(&bytes)
+    } else {
+        Fr::from_str(digits).map_err(|_| ZkencError::InputParse(format!("Invalid decimal field element: {}", s)))?
+    };
+
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
 /// Parse circuit inputs from JSON file
 /// Returns a vector of field elements in order (flattened if nested)
-fn parse_circuit_inputs(json_str: &str) -> Result<Vec<Fr>> {
-    let value: serde_json::Value = serde_json::from_str(json_str).context("Invalid JSON")?;
+fn parse_circuit_inputs(json_str: &str) -> Result<Vec<Fr>, ZkencError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json_str).map_err(|e| ZkencError::InputParse(format!("Invalid JSON: {}", e)))?;
 
-    let obj = value.as_object().context("JSON must be an object")?;
+    let obj = value
+        .as_object()
+        .ok_or_else(|| ZkencError::InputParse("JSON must be an object".to_string()))?;
 
     let mut result = Vec::new();
 
     // Flatten all values in the JSON object
-    fn flatten_value(val: &serde_json::Value, result: &mut Vec<Fr>) -> Result<()> {
+    fn flatten_value(val: &serde_json::Value, result: &mut Vec<Fr>) -> Result<(), ZkencError> {
         match val {
             serde_json::Value::Number(n) => {
                 let num = if let Some(u) = n.as_u64() {
                     Fr::from(u)
                 } else if let Some(i) = n.as_i64() {
-                    Fr::from(i as u64)
+                    // Negate in the field rather than bit-reinterpreting the
+                    // two's-complement value, matching
+                    // parse_signed_field_element's handling of a leading `-`.
+                    if i < 0 {
+                        -Fr::from(i.unsigned_abs())
+                    } else {
+                        Fr::from(i as u64)
+                    }
                 } else {
-                    anyhow::bail!("Unsupported number format");
+                    return Err(ZkencError::InputParse("Unsupported number format".to_string()));
                 };
                 result.push(num);
             }
             serde_json::Value::String(s) => {
-                // Try to parse as number
-                let num = s
-                    .parse::<u64>()
-                    .context("Failed to parse string as number")?;
-                result.push(Fr::from(num));
+                result.push(parse_signed_field_element(s)?);
             }
             serde_json::Value::Array(arr) => {
                 for item in arr {
                     flatten_value(item, result)?;
                 }
             }
-            _ => anyhow::bail!("Unsupported JSON type"),
+            _ => return Err(ZkencError::InputParse("Unsupported JSON type".to_string())),
         }
         Ok(())
     }
@@ -430,6 +734,52 @@ fn parse_circuit_inputs(json_str: &str) -> Result<Vec<Fr>> {
     Ok(result)
 }
 
+/// Parse a `--field` mode message file: a JSON array of field elements,
+/// each either a plain non-negative integer/decimal-string (for small
+/// values) or a `0x`-prefixed hex string of the element's little-endian
+/// canonical bytes (for values spanning the whole field).
+fn parse_field_message(json_str: &str) -> Result<Vec<Fr>> {
+    let value: serde_json::Value = serde_json::from_str(json_str).context("Invalid JSON")?;
+    let arr = value
+        .as_array()
+        .context("Field message JSON must be an array of field elements")?;
+
+    let mut result = Vec::with_capacity(arr.len());
+    for item in arr {
+        let element = match item {
+            serde_json::Value::Number(n) => n
+                .as_u64()
+                .map(Fr::from)
+                .context("Field elements must be non-negative integers")?,
+            serde_json::Value::String(s) => {
+                if let Some(hex_str) = s.strip_prefix("0x") {
+                    let bytes = hex::decode(hex_str).context("Invalid hex field element")?;
+                    Fr::from_le_bytes_mod_order(&bytes)
+                } else {
+                    s.parse::<u64>()
+                        .map(Fr::from)
+                        .context("Failed to parse field element string as an integer")?
+                }
+            }
+            _ => anyhow::bail!("Unsupported field element JSON type"),
+        };
+        result.push(element);
+    }
+
+    Ok(result)
+}
+
+/// Render decrypted `--field` mode plaintext back to the same JSON-array
+/// shape [`parse_field_message`] reads: each element as a `0x`-prefixed hex
+/// string of its little-endian canonical bytes.
+fn field_elements_to_json(elements: &[Fr]) -> String {
+    let strings: Vec<String> = elements
+        .iter()
+        .map(|e| format!("0x{}", hex::encode(e.into_bigint().to_bytes_le())))
+        .collect();
+    serde_json::to_string(&strings).expect("Vec<String> always serializes")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,13 +1,54 @@
-use aes::Aes256;
+use aes::{Aes128, Aes192, Aes256};
 use aes_gcm::{
     aead::{Aead, AeadCore, KeyInit, OsRng},
     Aes256Gcm, Nonce,
 };
 use anyhow::{Context, Result};
-use cipher::{KeyIvInit, StreamCipher};
-use ctr::Ctr64BE;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use cbc::{Decryptor as CbcDecryptor, Encryptor as CbcEncryptor};
+use cipher::block_padding::Pkcs7;
+use cipher::generic_array::GenericArray;
+use cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit, StreamCipher};
+use ctr::{Ctr128BE, Ctr32BE, Ctr64BE};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::io::{Read, Write};
 
-type Aes256Ctr = Ctr64BE<Aes256>;
+/// AES key size, selected automatically from the key bytes handed to
+/// `encrypt_ctr`/`decrypt_ctr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AesVariant {
+    Aes128,
+    Aes192,
+    Aes256,
+}
+
+impl AesVariant {
+    /// Select the variant matching a key's length (16/24/32 bytes).
+    fn from_key_len(len: usize) -> Result<Self> {
+        match len {
+            16 => Ok(AesVariant::Aes128),
+            24 => Ok(AesVariant::Aes192),
+            32 => Ok(AesVariant::Aes256),
+            other => anyhow::bail!(
+                "Key must be 16, 24, or 32 bytes (AES-128/192/256), got {}",
+                other
+            ),
+        }
+    }
+}
+
+/// Width of the incrementing counter block within the 16-byte CTR IV.
+///
+/// Real-world CTR ciphertexts in the wild use 32-bit, 64-bit, and 128-bit
+/// big-endian counters; the rest of the IV (if any) is a fixed nonce prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterWidth {
+    Bits32,
+    Bits64,
+    Bits128,
+}
 
 /// Encrypt data using AES-256-GCM (Galois/Counter Mode)
 ///
@@ -85,37 +126,310 @@ pub fn decrypt_gcm(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
     Ok(plaintext)
 }
 
-/// Encrypt data using AES-256-CTR (Counter Mode)
+/// Encrypt data using AES-256-GCM, authenticating `aad` alongside the
+/// ciphertext without including it in the output.
+///
+/// This is the same wire format as [`encrypt_gcm`] (`[nonce(12)][ciphertext
+/// + tag(16)]`) - `aad` must be supplied again, byte-for-byte, to
+/// [`decrypt_gcm_with_aad`], since it is not stored in the output.
+pub fn encrypt_gcm_with_aad(key: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create AES-GCM cipher")?;
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            aes_gcm::aead::Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let mut result = nonce.to_vec();
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+/// Decrypt data produced by [`encrypt_gcm_with_aad`]. `aad` must match what
+/// was passed to encryption exactly, or the authentication tag check fails.
+pub fn decrypt_gcm_with_aad(key: &[u8], data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+    if data.len() < 28 {
+        anyhow::bail!("Data too short, need at least 28 bytes (12 nonce + 16 tag)");
+    }
+
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create AES-GCM cipher")?;
+    let nonce = Nonce::from_slice(&data[..12]);
+    let ciphertext = &data[12..];
+
+    let plaintext = cipher
+        .decrypt(nonce, aes_gcm::aead::Payload { msg: ciphertext, aad })
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Decryption failed (authentication failed or corrupted data): {}",
+                e
+            )
+        })?;
+
+    Ok(plaintext)
+}
+
+/// Size of each plaintext chunk `encrypt_stream` seals independently.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Read up to `buf.len()` bytes, looping on short reads, stopping only at
+/// true EOF (a zero-byte read). Returns the number of bytes actually read,
+/// which is less than `buf.len()` only when the reader is exhausted.
+fn fill_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let n = reader.read(&mut buf[filled..])?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+    Ok(filled)
+}
+
+fn read_stream_chunk<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut buf = vec![0u8; STREAM_CHUNK_SIZE];
+    let n = fill_or_eof(reader, &mut buf)?;
+    buf.truncate(n);
+    Ok(buf)
+}
+
+/// Per-chunk nonce: a random 32-bit base salt (shared across the whole
+/// stream) plus a monotonically increasing 32-bit chunk counter.
+fn build_stream_nonce(salt: u32, counter: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[0..4].copy_from_slice(&salt.to_be_bytes());
+    nonce[4..8].copy_from_slice(&counter.to_be_bytes());
+    nonce
+}
+
+/// Encrypt `reader` to `writer` as a sequence of independently AEAD-sealed
+/// chunks, so large files never need to be loaded into memory at once.
+///
+/// # Format
+/// `[salt(4 bytes)]` followed, per chunk, by:
+/// `[is_last(1 byte)][sealed_len(4 bytes BE)][ciphertext + tag]`
+///
+/// The `is_last` byte is authenticated as each chunk's AEAD associated
+/// data, so an attacker who truncates the stream at a chunk boundary cannot
+/// relabel the last chunk they kept as the true final one - its tag was
+/// computed against `is_last = 0` and will fail to verify against `1`.
+pub fn encrypt_stream<R: Read, W: Write>(key: &[u8], mut reader: R, mut writer: W) -> Result<()> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create AES-GCM cipher")?;
+
+    let mut salt_bytes = [0u8; 4];
+    use rand::RngCore;
+    rand::thread_rng().fill_bytes(&mut salt_bytes);
+    let salt = u32::from_be_bytes(salt_bytes);
+    writer
+        .write_all(&salt_bytes)
+        .context("Failed to write stream salt header")?;
+
+    let mut counter: u32 = 0;
+    let mut current = read_stream_chunk(&mut reader)?;
+    loop {
+        // We only need to look ahead when `current` filled the whole chunk
+        // buffer - a short read already means the underlying reader is
+        // exhausted, so `current` is definitely the last chunk.
+        let next = if current.len() == STREAM_CHUNK_SIZE {
+            read_stream_chunk(&mut reader)?
+        } else {
+            Vec::new()
+        };
+        let is_last = next.is_empty();
+        let aad = [is_last as u8];
+
+        let nonce_bytes = build_stream_nonce(salt, counter);
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&nonce_bytes),
+                aes_gcm::aead::Payload {
+                    msg: &current,
+                    aad: &aad,
+                },
+            )
+            .map_err(|e| anyhow::anyhow!("Chunk encryption failed: {}", e))?;
+
+        writer.write_all(&aad)?;
+        writer.write_all(&(sealed.len() as u32).to_be_bytes())?;
+        writer.write_all(&sealed)?;
+
+        counter += 1;
+        if is_last {
+            break;
+        }
+        current = next;
+    }
+
+    Ok(())
+}
+
+/// Decrypt a stream produced by `encrypt_stream`.
+///
+/// Each chunk's tag is verified in order before its plaintext is written,
+/// and the stream must end exactly at a chunk whose authenticated
+/// `is_last` byte is set - ending earlier is reported as a truncation
+/// error rather than silently accepted.
+pub fn decrypt_stream<R: Read, W: Write>(key: &[u8], mut reader: R, mut writer: W) -> Result<()> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+    let cipher = Aes256Gcm::new_from_slice(key).context("Failed to create AES-GCM cipher")?;
+
+    let mut salt_bytes = [0u8; 4];
+    reader
+        .read_exact(&mut salt_bytes)
+        .context("Failed to read stream salt header")?;
+    let salt = u32::from_be_bytes(salt_bytes);
+
+    let mut counter: u32 = 0;
+    let mut saw_last = false;
+
+    loop {
+        let mut is_last_byte = [0u8; 1];
+        if fill_or_eof(&mut reader, &mut is_last_byte)? == 0 {
+            if saw_last {
+                break;
+            }
+            anyhow::bail!("Stream truncated: ended before the last-chunk marker was seen");
+        }
+
+        let mut len_bytes = [0u8; 4];
+        reader
+            .read_exact(&mut len_bytes)
+            .context("Stream truncated: missing chunk length")?;
+        let sealed_len = u32::from_be_bytes(len_bytes) as usize;
+
+        let mut sealed = vec![0u8; sealed_len];
+        reader
+            .read_exact(&mut sealed)
+            .context("Stream truncated: chunk body shorter than declared length")?;
+
+        let nonce_bytes = build_stream_nonce(salt, counter);
+        let plaintext = cipher
+            .decrypt(
+                Nonce::from_slice(&nonce_bytes),
+                aes_gcm::aead::Payload {
+                    msg: &sealed,
+                    aad: &is_last_byte,
+                },
+            )
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Chunk decryption failed (authentication failed or corrupted data): {}",
+                    e
+                )
+            })?;
+
+        writer
+            .write_all(&plaintext)
+            .context("Failed to write decrypted chunk")?;
+
+        saw_last = is_last_byte[0] != 0;
+        counter += 1;
+        if saw_last {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a CTR stream cipher's keystream over `data`, for any key/counter-width
+/// combination dispatched to a concrete `StreamCipher` type.
+fn run_ctr_keystream<C: KeyIvInit + StreamCipher>(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut cipher = C::new(
+        GenericArray::from_slice(key),
+        GenericArray::from_slice(iv),
+    );
+    let mut out = data.to_vec();
+    cipher.apply_keystream(&mut out);
+    out
+}
+
+/// Apply AES-CTR keystream for the variant/width selected from `key`, in
+/// either direction (CTR mode is its own inverse).
+fn apply_ctr(key: &[u8], iv: &[u8], data: &[u8], width: CounterWidth) -> Result<Vec<u8>> {
+    let variant = AesVariant::from_key_len(key.len())?;
+
+    let out = match (variant, width) {
+        (AesVariant::Aes128, CounterWidth::Bits32) => {
+            run_ctr_keystream::<Ctr32BE<Aes128>>(key, iv, data)
+        }
+        (AesVariant::Aes128, CounterWidth::Bits64) => {
+            run_ctr_keystream::<Ctr64BE<Aes128>>(key, iv, data)
+        }
+        (AesVariant::Aes128, CounterWidth::Bits128) => {
+            run_ctr_keystream::<Ctr128BE<Aes128>>(key, iv, data)
+        }
+        (AesVariant::Aes192, CounterWidth::Bits32) => {
+            run_ctr_keystream::<Ctr32BE<Aes192>>(key, iv, data)
+        }
+        (AesVariant::Aes192, CounterWidth::Bits64) => {
+            run_ctr_keystream::<Ctr64BE<Aes192>>(key, iv, data)
+        }
+        (AesVariant::Aes192, CounterWidth::Bits128) => {
+            run_ctr_keystream::<Ctr128BE<Aes192>>(key, iv, data)
+        }
+        (AesVariant::Aes256, CounterWidth::Bits32) => {
+            run_ctr_keystream::<Ctr32BE<Aes256>>(key, iv, data)
+        }
+        (AesVariant::Aes256, CounterWidth::Bits64) => {
+            run_ctr_keystream::<Ctr64BE<Aes256>>(key, iv, data)
+        }
+        (AesVariant::Aes256, CounterWidth::Bits128) => {
+            run_ctr_keystream::<Ctr128BE<Aes256>>(key, iv, data)
+        }
+    };
+
+    Ok(out)
+}
+
+/// Encrypt data using AES-CTR (Counter Mode)
 ///
 /// CTR mode provides confidentiality but not authenticity.
 /// Consider using GCM if you need authentication.
 ///
+/// The AES key size (128/192/256) is selected automatically from `key`'s
+/// length; `width` picks how many of the IV's trailing bytes are treated as
+/// the incrementing counter rather than a fixed nonce prefix.
+///
 /// # Format
 /// Output: [iv(16 bytes)][ciphertext]
 ///
 /// # Arguments
-/// * `key` - 32-byte encryption key
+/// * `key` - 16, 24, or 32-byte encryption key
 /// * `plaintext` - Data to encrypt
+/// * `width` - CTR counter block width to use
 ///
 /// # Returns
 /// Combined IV + ciphertext
-pub fn encrypt_ctr(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
-    // Validate key length
-    if key.len() != 32 {
-        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
-    }
+pub fn encrypt_ctr(key: &[u8], plaintext: &[u8], width: CounterWidth) -> Result<Vec<u8>> {
+    // Validate key length (and select the AES variant)
+    AesVariant::from_key_len(key.len())?;
 
     // Generate random IV (16 bytes for AES)
     let mut iv = [0u8; 16];
     use rand::RngCore;
     rand::thread_rng().fill_bytes(&mut iv);
 
-    // Create cipher
-    let mut cipher = Aes256Ctr::new(key.into(), &iv.into());
-
     // Encrypt (CTR mode is symmetric - same operation for encrypt/decrypt)
-    let mut ciphertext = plaintext.to_vec();
-    cipher.apply_keystream(&mut ciphertext);
+    let ciphertext = apply_ctr(key, &iv, plaintext, width)?;
 
     // Combine IV + ciphertext
     let mut result = iv.to_vec();
@@ -124,19 +438,18 @@ pub fn encrypt_ctr(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
     Ok(result)
 }
 
-/// Decrypt data using AES-256-CTR
+/// Decrypt data using AES-CTR
 ///
 /// # Arguments
-/// * `key` - 32-byte encryption key (same as used for encryption)
+/// * `key` - 16, 24, or 32-byte encryption key (same as used for encryption)
 /// * `data` - Combined IV + ciphertext
+/// * `width` - CTR counter block width used for encryption
 ///
 /// # Returns
 /// Original plaintext
-pub fn decrypt_ctr(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
-    // Validate key length
-    if key.len() != 32 {
-        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
-    }
+pub fn decrypt_ctr(key: &[u8], data: &[u8], width: CounterWidth) -> Result<Vec<u8>> {
+    // Validate key length (and select the AES variant)
+    AesVariant::from_key_len(key.len())?;
 
     // Validate minimum data length (IV)
     if data.len() < 16 {
@@ -149,16 +462,413 @@ pub fn decrypt_ctr(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
     // Extract ciphertext (rest)
     let ciphertext = &data[16..];
 
-    // Create cipher
-    let mut cipher = Aes256Ctr::new(key.into(), iv.into());
-
     // Decrypt (CTR mode is symmetric)
-    let mut plaintext = ciphertext.to_vec();
-    cipher.apply_keystream(&mut plaintext);
+    apply_ctr(key, iv, ciphertext, width)
+}
+
+/// HMAC-SHA256 tag length, in bytes.
+const HMAC_TAG_LEN: usize = 32;
+
+/// Derive an encryption subkey and a MAC subkey from `key` via HKDF-SHA256,
+/// domain-separated by info string so the two subkeys are independent.
+fn derive_ctr_subkeys(key: &[u8]) -> Result<([u8; 32], [u8; 32])> {
+    let hkdf = Hkdf::<Sha256>::new(None, key);
+
+    let mut enc_key = [0u8; 32];
+    hkdf.expand(b"zkenc-ctr-encryption", &mut enc_key)
+        .map_err(|e| anyhow::anyhow!("HKDF expand failed for encryption subkey: {}", e))?;
+
+    let mut mac_key = [0u8; 32];
+    hkdf.expand(b"zkenc-ctr-mac", &mut mac_key)
+        .map_err(|e| anyhow::anyhow!("HKDF expand failed for MAC subkey: {}", e))?;
+
+    Ok((enc_key, mac_key))
+}
+
+fn compute_ctr_tag(mac_key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Result<[u8; HMAC_TAG_LEN]> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(mac_key).context("Failed to create HMAC-SHA256")?;
+    mac.update(iv);
+    mac.update(ciphertext);
+    let tag = mac.finalize().into_bytes();
+
+    let mut out = [0u8; HMAC_TAG_LEN];
+    out.copy_from_slice(&tag);
+    Ok(out)
+}
+
+/// Constant-time byte comparison: every byte is visited regardless of
+/// earlier mismatches, so the runtime does not leak how many leading bytes
+/// of the tag matched.
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        diff |= x ^ y;
+    }
+    diff == 0
+}
+
+/// Encrypt data using AES-CTR with an encrypt-then-HMAC integrity tag.
+///
+/// The 32-byte input `key` is expanded via HKDF-SHA256 into independent
+/// encryption and MAC subkeys, the plaintext is encrypted with AES-256-CTR,
+/// and an HMAC-SHA256 tag over `iv || ciphertext` is appended. This gives
+/// CTR's streaming shape the integrity guarantee its plain form lacks.
+///
+/// # Format
+/// Output: [iv(16 bytes)][ciphertext][tag(32 bytes)]
+///
+/// # Arguments
+/// * `key` - 32-byte input key (expanded into encryption + MAC subkeys)
+/// * `plaintext` - Data to encrypt
+/// * `width` - CTR counter block width to use
+pub fn encrypt_ctr_authenticated(
+    key: &[u8],
+    plaintext: &[u8],
+    width: CounterWidth,
+) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+
+    let (enc_key, mac_key) = derive_ctr_subkeys(key)?;
+
+    let mut iv = [0u8; 16];
+    use rand::RngCore;
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let ciphertext = apply_ctr(&enc_key, &iv, plaintext, width)?;
+    let tag = compute_ctr_tag(&mac_key, &iv, &ciphertext)?;
+
+    let mut result = Vec::with_capacity(iv.len() + ciphertext.len() + tag.len());
+    result.extend_from_slice(&iv);
+    result.extend_from_slice(&ciphertext);
+    result.extend_from_slice(&tag);
+
+    Ok(result)
+}
+
+/// Decrypt data produced by `encrypt_ctr_authenticated`.
+///
+/// The HMAC-SHA256 tag is recomputed and compared against the stored tag
+/// using a constant-time equality check before the CTR keystream is ever
+/// run, so a corrupted or forged ciphertext is rejected instead of silently
+/// decrypted.
+///
+/// # Arguments
+/// * `key` - 32-byte input key (same as used for encryption)
+/// * `data` - Combined IV + ciphertext + tag
+/// * `width` - CTR counter block width used for encryption
+pub fn decrypt_ctr_authenticated(
+    key: &[u8],
+    data: &[u8],
+    width: CounterWidth,
+) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+
+    if data.len() < 16 + HMAC_TAG_LEN {
+        anyhow::bail!(
+            "Data too short, need at least {} bytes (16 IV + {} tag)",
+            16 + HMAC_TAG_LEN,
+            HMAC_TAG_LEN
+        );
+    }
+
+    let (enc_key, mac_key) = derive_ctr_subkeys(key)?;
+
+    let iv = &data[..16];
+    let ciphertext = &data[16..data.len() - HMAC_TAG_LEN];
+    let stored_tag = &data[data.len() - HMAC_TAG_LEN..];
+
+    let expected_tag = compute_ctr_tag(&mac_key, iv, ciphertext)?;
+    if !ct_eq(&expected_tag, stored_tag) {
+        anyhow::bail!("Authentication failed: HMAC tag mismatch");
+    }
+
+    apply_ctr(&enc_key, iv, ciphertext, width)
+}
+
+/// Encrypt data using AES-256-CBC with PKCS#7 padding.
+///
+/// CBC provides confidentiality but not authenticity - prefer
+/// [`encrypt_gcm`] or [`encrypt_ctr_authenticated`] unless CBC is required
+/// for interoperability with another system.
+///
+/// # Format
+/// Output: [iv(16 bytes)][ciphertext]
+///
+/// # Arguments
+/// * `key` - 32-byte encryption key
+/// * `plaintext` - Data to encrypt
+pub fn encrypt_cbc(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+
+    let mut iv = [0u8; 16];
+    use rand::RngCore;
+    rand::thread_rng().fill_bytes(&mut iv);
+
+    let ciphertext = CbcEncryptor::<Aes256>::new(key.into(), &iv.into())
+        .encrypt_padded_vec_mut::<Pkcs7>(plaintext);
+
+    let mut result = iv.to_vec();
+    result.extend_from_slice(&ciphertext);
+    Ok(result)
+}
+
+/// Decrypt data produced by `encrypt_cbc`.
+///
+/// # Arguments
+/// * `key` - 32-byte encryption key (same as used for encryption)
+/// * `data` - Combined IV + ciphertext
+pub fn decrypt_cbc(key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != 32 {
+        anyhow::bail!("Key must be 32 bytes (256 bits), got {}", key.len());
+    }
+    if data.len() < 16 {
+        anyhow::bail!("Data too short, need at least 16 bytes for IV");
+    }
+
+    let iv = &data[..16];
+    let ciphertext = &data[16..];
+
+    let plaintext = CbcDecryptor::<Aes256>::new(key.into(), iv.into())
+        .decrypt_padded_vec_mut::<Pkcs7>(ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decryption failed (bad padding or corrupted data): {}", e))?;
 
     Ok(plaintext)
 }
 
+/// Which kind of artifact an armored blob carries. Recorded in the
+/// `BEGIN`/`END` delimiter lines (the header block below them carries only
+/// the version and curve), so `dearmor_expect` can
+/// reject a blob produced for a different command without even looking at
+/// its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArmorType {
+    /// A `zkenc_core::Ciphertext` (the output of `encap`, i.e. `witness.ct`).
+    Ciphertext,
+    /// A `zkenc_core::EncapKey` (the CRS embedded in a ciphertext).
+    EncapKey,
+    /// A `zkenc_core::Key` (a `key_encap.bin`/`key_decap.bin` symmetric key).
+    Key,
+    /// A combined-format artifact produced by `encrypt_command`.
+    CombinedMessage,
+}
+
+impl ArmorType {
+    fn label(self) -> &'static str {
+        match self {
+            ArmorType::Ciphertext => "CIPHERTEXT",
+            ArmorType::EncapKey => "ENCAP KEY",
+            ArmorType::Key => "KEY",
+            ArmorType::CombinedMessage => "COMBINED MESSAGE",
+        }
+    }
+
+    fn from_label(label: &str) -> Result<Self> {
+        match label {
+            "CIPHERTEXT" => Ok(ArmorType::Ciphertext),
+            "ENCAP KEY" => Ok(ArmorType::EncapKey),
+            "KEY" => Ok(ArmorType::Key),
+            "COMBINED MESSAGE" => Ok(ArmorType::CombinedMessage),
+            other => anyhow::bail!("Unknown armor type: {}", other),
+        }
+    }
+}
+
+/// The pairing curve an armored artifact was produced under, recorded in
+/// the header block so a ciphertext armored for one curve is rejected
+/// instead of silently failing to deserialize under another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveId {
+    Bn254,
+    Bls12_381,
+}
+
+impl CurveId {
+    fn label(self) -> &'static str {
+        match self {
+            CurveId::Bn254 => "bn254",
+            CurveId::Bls12_381 => "bls12-381",
+        }
+    }
+
+    fn from_label(label: &str) -> Result<Self> {
+        match label {
+            "bn254" => Ok(CurveId::Bn254),
+            "bls12-381" => Ok(CurveId::Bls12_381),
+            other => anyhow::bail!("Unknown curve identifier in armor header: {}", other),
+        }
+    }
+}
+
+/// Format version recorded in an armored block's header. Bumped if the
+/// header or body layout changes incompatibly.
+const ARMOR_FORMAT_VERSION: u8 = 1;
+
+/// ASCII-armor `data` (a raw `CanonicalSerialize`d artifact) OpenPGP-style:
+/// `-----BEGIN ZKENC <TYPE>-----`, a small header block naming the format
+/// version and pairing curve, a blank line, the base64 body wrapped at 64
+/// columns, a trailing CRC-24 checksum line, and a matching `END` delimiter.
+pub fn armor(data: &[u8], armor_type: ArmorType, curve: CurveId) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("-----BEGIN ZKENC {}-----\n", armor_type.label()));
+    out.push_str(&format!("Version: {}\n", ARMOR_FORMAT_VERSION));
+    out.push_str(&format!("Curve: {}\n", curve.label()));
+    out.push('\n');
+    for line in BASE64.encode(data).as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).expect("base64 alphabet is ASCII"));
+        out.push('\n');
+    }
+    out.push('=');
+    out.push_str(&BASE64.encode(crc24(data).to_be_bytes()[1..].to_vec()));
+    out.push('\n');
+    out.push_str(&format!("-----END ZKENC {}-----\n", armor_type.label()));
+    out
+}
+
+/// Parse a block produced by `armor`, verifying its CRC-24 checksum and
+/// returning the decoded payload along with the type/curve declared in its
+/// header, so the caller can decide whether they match what it expected.
+pub fn dearmor(armored: &str) -> Result<(Vec<u8>, ArmorType, CurveId)> {
+    let begin_marker_start = armored
+        .find("-----BEGIN ZKENC ")
+        .context("Missing BEGIN ZKENC delimiter")?;
+    let begin_line_end = armored[begin_marker_start..]
+        .find('\n')
+        .map(|i| begin_marker_start + i)
+        .context("Malformed BEGIN ZKENC delimiter line")?;
+    let type_label = armored[begin_marker_start..begin_line_end]
+        .strip_prefix("-----BEGIN ZKENC ")
+        .and_then(|s| s.strip_suffix("-----"))
+        .context("Malformed BEGIN ZKENC delimiter")?
+        .trim();
+    let armor_type = ArmorType::from_label(type_label)?;
+
+    let end_marker = format!("-----END ZKENC {}-----", type_label);
+    let end_idx = armored
+        .find(&end_marker)
+        .context("Missing matching END ZKENC delimiter")?;
+
+    let body = &armored[begin_line_end + 1..end_idx];
+
+    let mut version = None;
+    let mut curve = None;
+    let mut checksum_line = None;
+    let mut base64_data = String::new();
+    let mut in_headers = true;
+
+    for line in body.lines() {
+        let line = line.trim();
+        if in_headers {
+            if line.is_empty() {
+                in_headers = false;
+                continue;
+            }
+            if let Some(v) = line.strip_prefix("Version:") {
+                version = Some(v.trim().to_string());
+            } else if let Some(c) = line.strip_prefix("Curve:") {
+                curve = Some(CurveId::from_label(c.trim())?);
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(sum) = line.strip_prefix('=') {
+            checksum_line = Some(sum.to_string());
+        } else {
+            base64_data.push_str(line);
+        }
+    }
+
+    let version = version.context("Missing Version header in armored block")?;
+    if version != ARMOR_FORMAT_VERSION.to_string() {
+        anyhow::bail!("Unsupported armor format version: {}", version);
+    }
+    let curve = curve.context("Missing Curve header in armored block")?;
+
+    let data = BASE64
+        .decode(&base64_data)
+        .context("Failed to base64-decode armored body")?;
+
+    let checksum_b64 = checksum_line.context("Missing CRC-24 checksum line in armored block")?;
+    let checksum_bytes = BASE64
+        .decode(&checksum_b64)
+        .context("Failed to base64-decode CRC-24 checksum")?;
+    if checksum_bytes.len() != 3 {
+        anyhow::bail!("Malformed CRC-24 checksum line");
+    }
+    let expected = crc24(&data).to_be_bytes();
+    if checksum_bytes != expected[1..] {
+        anyhow::bail!("Armor checksum mismatch: container is corrupted or truncated");
+    }
+
+    Ok((data, armor_type, curve))
+}
+
+/// Like [`dearmor`], but bails if the block's declared type or curve don't
+/// match what the caller expects, instead of handing back a mismatched
+/// artifact for the caller to misuse.
+pub fn dearmor_expect(
+    armored: &str,
+    expected_type: ArmorType,
+    expected_curve: CurveId,
+) -> Result<Vec<u8>> {
+    let (data, armor_type, curve) = dearmor(armored)?;
+    if armor_type != expected_type {
+        anyhow::bail!(
+            "Expected a {:?} armor block, got {:?}",
+            expected_type,
+            armor_type
+        );
+    }
+    if curve != expected_curve {
+        anyhow::bail!(
+            "Expected curve {:?}, armored block declares {:?}",
+            expected_curve,
+            curve
+        );
+    }
+    Ok(data)
+}
+
+/// `true` if `data` looks like it starts with an armored block rather than
+/// raw bytes, so readers can transparently dearmor without a dedicated flag.
+pub fn looks_armored(data: &[u8]) -> bool {
+    let sniff_len = data.len().min(32);
+    std::str::from_utf8(&data[..sniff_len])
+        .map(|s| s.trim_start().starts_with("-----BEGIN ZKENC "))
+        .unwrap_or(false)
+}
+
+/// OpenPGP-style CRC-24 (poly 0x864CFB, init 0xB704CE), the same variant
+/// OpenPGP ASCII armor uses, so corrupted armored blocks are detectable the
+/// standard way.
+fn crc24(data: &[u8]) -> u32 {
+    const CRC24_INIT: u32 = 0x00B704CE;
+    const CRC24_POLY: u32 = 0x01864CFB;
+
+    let mut crc = CRC24_INIT;
+    for &byte in data {
+        crc ^= (byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x01000000 != 0 {
+                crc ^= CRC24_POLY;
+            }
+        }
+    }
+    crc & 0x00FFFFFF
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,18 +885,194 @@ mod tests {
         assert_eq!(plaintext.to_vec(), decrypted);
     }
 
+    #[test]
+    fn test_gcm_with_aad_roundtrip() {
+        let key = b"12345678901234567890123456789012"; // 32 bytes
+        let plaintext = b"Hello, World!";
+        let aad = b"public-inputs-bytes";
+
+        let encrypted = encrypt_gcm_with_aad(key, plaintext, aad).unwrap();
+        let decrypted = decrypt_gcm_with_aad(key, &encrypted, aad).unwrap();
+        assert_eq!(plaintext.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn test_gcm_with_aad_rejects_mismatched_aad() {
+        let key = b"12345678901234567890123456789012";
+        let plaintext = b"Hello, World!";
+
+        let encrypted = encrypt_gcm_with_aad(key, plaintext, b"aad-a").unwrap();
+        let result = decrypt_gcm_with_aad(key, &encrypted, b"aad-b");
+        assert!(result.is_err(), "Mismatched AAD should fail authentication");
+    }
+
     #[test]
     fn test_ctr_roundtrip() {
         let key = b"12345678901234567890123456789012"; // 32 bytes
         let plaintext = b"Hello, World!";
 
-        let encrypted = encrypt_ctr(key, plaintext).unwrap();
+        let encrypted = encrypt_ctr(key, plaintext, CounterWidth::Bits64).unwrap();
         assert!(encrypted.len() > plaintext.len());
 
-        let decrypted = decrypt_ctr(key, &encrypted).unwrap();
+        let decrypted = decrypt_ctr(key, &encrypted, CounterWidth::Bits64).unwrap();
         assert_eq!(plaintext.to_vec(), decrypted);
     }
 
+    #[test]
+    fn test_ctr_roundtrip_all_variants() {
+        let keys: [&[u8]; 3] = [
+            b"1234567890123456",                 // 16 bytes: AES-128
+            b"123456789012345678901234",         // 24 bytes: AES-192
+            b"12345678901234567890123456789012", // 32 bytes: AES-256
+        ];
+        let widths = [
+            CounterWidth::Bits32,
+            CounterWidth::Bits64,
+            CounterWidth::Bits128,
+        ];
+        let plaintext = b"Hello, World!";
+
+        for key in keys {
+            for width in widths {
+                let encrypted = encrypt_ctr(key, plaintext, width).unwrap();
+                let decrypted = decrypt_ctr(key, &encrypted, width).unwrap();
+                assert_eq!(plaintext.to_vec(), decrypted);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ctr_authenticated_roundtrip() {
+        let key = b"12345678901234567890123456789012"; // 32 bytes
+        let plaintext = b"Hello, World!";
+
+        let encrypted =
+            encrypt_ctr_authenticated(key, plaintext, CounterWidth::Bits64).unwrap();
+        assert_eq!(encrypted.len(), 16 + plaintext.len() + HMAC_TAG_LEN);
+
+        let decrypted =
+            decrypt_ctr_authenticated(key, &encrypted, CounterWidth::Bits64).unwrap();
+        assert_eq!(plaintext.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn test_ctr_authenticated_detects_tampering() {
+        let key = b"12345678901234567890123456789012";
+        let plaintext = b"Secret message";
+
+        let mut encrypted =
+            encrypt_ctr_authenticated(key, plaintext, CounterWidth::Bits64).unwrap();
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 0x01; // flip a bit in the tag
+
+        let result = decrypt_ctr_authenticated(key, &encrypted, CounterWidth::Bits64);
+        assert!(result.is_err(), "Tampered ciphertext should fail authentication");
+    }
+
+    #[test]
+    fn test_ctr_authenticated_wrong_key() {
+        let key1 = b"12345678901234567890123456789012";
+        let key2 = b"99999999999999999999999999999999";
+        let plaintext = b"Secret message";
+
+        let encrypted =
+            encrypt_ctr_authenticated(key1, plaintext, CounterWidth::Bits64).unwrap();
+        let result = decrypt_ctr_authenticated(key2, &encrypted, CounterWidth::Bits64);
+
+        assert!(result.is_err(), "Should fail with wrong key");
+    }
+
+    #[test]
+    fn test_stream_roundtrip_single_chunk() {
+        let key = b"12345678901234567890123456789012";
+        let plaintext = b"Hello, streaming World!";
+
+        let mut encrypted = Vec::new();
+        encrypt_stream(key, &plaintext[..], &mut encrypted).unwrap();
+
+        let mut decrypted = Vec::new();
+        decrypt_stream(key, &encrypted[..], &mut decrypted).unwrap();
+
+        assert_eq!(plaintext.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn test_stream_roundtrip_multiple_chunks() {
+        let key = b"12345678901234567890123456789012";
+        // More than two chunk's worth so encrypt_stream exercises the
+        // lookahead across several non-final chunks.
+        let plaintext: Vec<u8> = (0..(STREAM_CHUNK_SIZE * 2 + 123))
+            .map(|i| (i % 251) as u8)
+            .collect();
+
+        let mut encrypted = Vec::new();
+        encrypt_stream(key, &plaintext[..], &mut encrypted).unwrap();
+
+        let mut decrypted = Vec::new();
+        decrypt_stream(key, &encrypted[..], &mut decrypted).unwrap();
+
+        assert_eq!(plaintext, decrypted);
+    }
+
+    #[test]
+    fn test_stream_roundtrip_empty_input() {
+        let key = b"12345678901234567890123456789012";
+
+        let mut encrypted = Vec::new();
+        encrypt_stream(key, &[][..], &mut encrypted).unwrap();
+
+        let mut decrypted = Vec::new();
+        decrypt_stream(key, &encrypted[..], &mut decrypted).unwrap();
+
+        assert!(decrypted.is_empty());
+    }
+
+    #[test]
+    fn test_stream_detects_truncation() {
+        let key = b"12345678901234567890123456789012";
+        let plaintext: Vec<u8> = (0..(STREAM_CHUNK_SIZE + 100)).map(|i| i as u8).collect();
+
+        let mut encrypted = Vec::new();
+        encrypt_stream(key, &plaintext[..], &mut encrypted).unwrap();
+
+        // Drop the final chunk record so the stream ends right after a
+        // complete non-last chunk: [salt(4)][is_last(1)][len(4)][sealed].
+        let first_record_end = 4 + 1 + 4 + (STREAM_CHUNK_SIZE + 16);
+        let truncated = &encrypted[..first_record_end];
+
+        let mut decrypted = Vec::new();
+        let result = decrypt_stream(key, truncated, &mut decrypted);
+
+        assert!(result.is_err(), "Truncated stream should be rejected");
+    }
+
+    #[test]
+    fn test_cbc_roundtrip() {
+        let key = b"12345678901234567890123456789012"; // 32 bytes
+        let plaintext = b"Hello, World!";
+
+        let encrypted = encrypt_cbc(key, plaintext).unwrap();
+        assert!(encrypted.len() > plaintext.len());
+
+        let decrypted = decrypt_cbc(key, &encrypted).unwrap();
+        assert_eq!(plaintext.to_vec(), decrypted);
+    }
+
+    #[test]
+    fn test_cbc_wrong_key() {
+        let key1 = b"12345678901234567890123456789012";
+        let key2 = b"99999999999999999999999999999999";
+        let plaintext = b"Secret message, long enough to span a couple of CBC blocks";
+
+        let encrypted = encrypt_cbc(key1, plaintext).unwrap();
+        let result = decrypt_cbc(key2, &encrypted);
+
+        // Not authenticated, but a wrong key almost always produces invalid
+        // PKCS#7 padding, so this is expected to fail rather than silently
+        // return garbage plaintext.
+        assert!(result.is_err(), "Should fail with wrong key");
+    }
+
     #[test]
     fn test_gcm_wrong_key() {
         let key1 = b"12345678901234567890123456789012";
@@ -205,6 +1091,74 @@ mod tests {
         let plaintext = b"data";
 
         assert!(encrypt_gcm(short_key, plaintext).is_err());
-        assert!(encrypt_ctr(short_key, plaintext).is_err());
+        assert!(encrypt_ctr(short_key, plaintext, CounterWidth::Bits64).is_err());
+    }
+
+    #[test]
+    fn test_armor_roundtrip() {
+        let data = b"some ciphertext bytes, not actually CanonicalSerialize here".to_vec();
+        let armored = armor(&data, ArmorType::Ciphertext, CurveId::Bn254);
+
+        assert!(armored.starts_with("-----BEGIN ZKENC CIPHERTEXT-----"));
+        assert!(armored.trim_end().ends_with("-----END ZKENC CIPHERTEXT-----"));
+        assert!(looks_armored(armored.as_bytes()));
+
+        let (decoded, armor_type, curve) = dearmor(&armored).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(armor_type, ArmorType::Ciphertext);
+        assert_eq!(curve, CurveId::Bn254);
+    }
+
+    #[test]
+    fn test_dearmor_expect_accepts_matching_type_and_curve() {
+        let data = b"key bytes".to_vec();
+        let armored = armor(&data, ArmorType::Key, CurveId::Bn254);
+
+        let decoded = dearmor_expect(&armored, ArmorType::Key, CurveId::Bn254).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_dearmor_expect_rejects_mismatched_type() {
+        let armored = armor(b"key bytes", ArmorType::Key, CurveId::Bn254);
+        let result = dearmor_expect(&armored, ArmorType::Ciphertext, CurveId::Bn254);
+        assert!(result.is_err(), "Should reject a KEY block where a CIPHERTEXT was expected");
+    }
+
+    #[test]
+    fn test_dearmor_expect_rejects_mismatched_curve() {
+        let armored = armor(b"ciphertext bytes", ArmorType::Ciphertext, CurveId::Bls12_381);
+        let result = dearmor_expect(&armored, ArmorType::Ciphertext, CurveId::Bn254);
+        assert!(result.is_err(), "Should reject a block armored for a different curve");
+    }
+
+    #[test]
+    fn test_dearmor_detects_corruption() {
+        let armored = armor(b"hello world", ArmorType::CombinedMessage, CurveId::Bn254);
+
+        let mut lines: Vec<&str> = armored.lines().collect();
+        let data_line_idx = lines
+            .iter()
+            .position(|l| {
+                !l.is_empty()
+                    && !l.starts_with('-')
+                    && !l.starts_with('=')
+                    && !l.starts_with("Version:")
+                    && !l.starts_with("Curve:")
+            })
+            .unwrap();
+        let mut corrupted_line = lines[data_line_idx].to_string();
+        let first_char = corrupted_line.chars().next().unwrap();
+        let replacement = if first_char == 'A' { 'B' } else { 'A' };
+        corrupted_line.replace_range(0..1, &replacement.to_string());
+        lines[data_line_idx] = &corrupted_line;
+        let corrupted_armor = lines.join("\n");
+
+        assert!(dearmor(&corrupted_armor).is_err());
+    }
+
+    #[test]
+    fn test_looks_armored_false_for_raw_bytes() {
+        assert!(!looks_armored(&[0u8, 1, 2, 3, 4, 5]));
     }
 }
@@ -0,0 +1,114 @@
+// envelope.rs - Hybrid KEM/DEM envelope tying witness-encryption to AES
+//
+// This is the ECIES-style KEM-then-symmetric composition adapted to witness
+// encryption: `seal` runs zkenc-core's `encap` to get a KEM ciphertext and a
+// symmetric key, HKDF-expands that key to 32 bytes, and AES-256-GCM encrypts
+// the plaintext with it. `open` runs `decap` to recover the same key (from a
+// circuit with its full witness assigned) and decrypts.
+
+use anyhow::{Context, Result};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_relations::gr1cs::ConstraintSynthesizer;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use hkdf::Hkdf;
+use sha2::Sha256;
+use zkenc_core::{decap, encap, Ciphertext, Key};
+
+use crate::crypto;
+
+/// Combined container produced by `seal`: the witness-encryption KEM
+/// ciphertext and the AES-256-GCM-sealed plaintext.
+///
+/// # Format
+/// `[kem_len(4 bytes BE)][kem ciphertext][dem ciphertext]`
+pub struct Envelope {
+    pub kem_ciphertext: Vec<u8>,
+    pub dem_ciphertext: Vec<u8>,
+}
+
+impl Envelope {
+    /// Serialize to the combined on-disk format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out =
+            Vec::with_capacity(4 + self.kem_ciphertext.len() + self.dem_ciphertext.len());
+        out.extend_from_slice(&(self.kem_ciphertext.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.kem_ciphertext);
+        out.extend_from_slice(&self.dem_ciphertext);
+        out
+    }
+
+    /// Parse the combined on-disk format.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            anyhow::bail!("Envelope too short: missing KEM ciphertext length");
+        }
+        let kem_len =
+            u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        if data.len() < 4 + kem_len {
+            anyhow::bail!("Envelope truncated: declared KEM ciphertext length exceeds data");
+        }
+
+        Ok(Self {
+            kem_ciphertext: data[4..4 + kem_len].to_vec(),
+            dem_ciphertext: data[4 + kem_len..].to_vec(),
+        })
+    }
+}
+
+/// Expand a witness-encryption KEM key into a 32-byte AES-256-GCM key via
+/// HKDF-SHA256, domain-separated so the DEM key is never the raw KEM key.
+fn derive_dem_key(key: &Key) -> Result<[u8; 32]> {
+    let hkdf = Hkdf::<Sha256>::new(None, key.as_bytes());
+    let mut dem_key = [0u8; 32];
+    hkdf.expand(b"zkenc-envelope-dem", &mut dem_key)
+        .map_err(|e| anyhow::anyhow!("HKDF expand failed for DEM key: {}", e))?;
+    Ok(dem_key)
+}
+
+/// Seal `plaintext` under `circuit`'s witness-encryption key.
+///
+/// Runs `encap` (with only public inputs assigned on `circuit`) to get a
+/// KEM ciphertext and symmetric key, then AES-256-GCM encrypts `plaintext`
+/// with an HKDF-expanded form of that key.
+pub fn seal<C, R>(circuit: C, plaintext: &[u8], rng: &mut R) -> Result<Envelope>
+where
+    C: ConstraintSynthesizer<<Bn254 as Pairing>::ScalarField>,
+    R: RngCore,
+{
+    let (ciphertext, key) = encap::<Bn254, _, _>(circuit, rng)
+        .map_err(|e| anyhow::anyhow!("Encap failed: {:?}", e))?;
+
+    let mut kem_ciphertext = Vec::new();
+    ciphertext
+        .serialize_compressed(&mut kem_ciphertext)
+        .context("Failed to serialize KEM ciphertext")?;
+
+    let dem_key = derive_dem_key(&key)?;
+    let dem_ciphertext =
+        crypto::encrypt_gcm(&dem_key, plaintext).context("Failed to encrypt plaintext")?;
+
+    Ok(Envelope {
+        kem_ciphertext,
+        dem_ciphertext,
+    })
+}
+
+/// Open an `Envelope` given `circuit` with its full witness assigned.
+///
+/// Runs `decap` to recover the symmetric key, then AES-256-GCM decrypts the
+/// DEM ciphertext with an HKDF-expanded form of that key.
+pub fn open<C>(circuit: C, envelope: &Envelope) -> Result<Vec<u8>>
+where
+    C: ConstraintSynthesizer<<Bn254 as Pairing>::ScalarField>,
+{
+    let kem_ciphertext = Ciphertext::<Bn254>::deserialize_compressed(&envelope.kem_ciphertext[..])
+        .context("Failed to deserialize KEM ciphertext")?;
+
+    let key = decap::<Bn254, _>(circuit, &kem_ciphertext)
+        .map_err(|e| anyhow::anyhow!("Decap failed: {:?}", e))?;
+
+    let dem_key = derive_dem_key(&key)?;
+    crypto::decrypt_gcm(&dem_key, &envelope.dem_ciphertext).context("Failed to decrypt plaintext")
+}
@@ -0,0 +1,48 @@
+// error.rs - Structured error type for CLI command failures
+//
+// The command functions in commands.rs used to thread every failure
+// through anyhow::Result, so a caller only ever saw an opaque message
+// string. ZkencError instead distinguishes the failure classes those
+// commands can actually produce, mirroring the move to precise
+// decoder/encoder error types elsewhere in this crate (see
+// codec::CodecError) - a caller can match on e.g. `ZkencError::DecapFailed`
+// instead of grepping the Display string for "Decap failed".
+
+use thiserror::Error;
+
+/// A command-level failure from `encap_command`/`decap_command`/
+/// `encrypt_command`/`decrypt_command`/`parse_circuit_inputs`.
+#[derive(Debug, Error)]
+pub enum ZkencError {
+    /// Failed to load or parse the R1CS circuit file.
+    #[error("Failed to load circuit: {0}")]
+    CircuitLoad(String),
+
+    /// Failed to read or parse a public-input or message JSON file.
+    #[error("Failed to parse input: {0}")]
+    InputParse(String),
+
+    /// Failed to load, parse, or convert a snarkjs witness (`.wtns`) file.
+    #[error("Failed to load witness: {0}")]
+    WitnessLoad(String),
+
+    /// A ciphertext artifact's shape didn't match what was expected - for
+    /// example the `--aead`/`--field` mode recorded in the ciphertext
+    /// doesn't match the flag passed on the command line, or the bytes
+    /// don't deserialize as the expected type at all.
+    #[error("Ciphertext mismatch: expected {expected}, got {got}")]
+    CiphertextDecode { expected: String, got: String },
+
+    /// `zkenc_core::encap` failed.
+    #[error("Encap failed: {0}")]
+    EncapFailed(String),
+
+    /// `zkenc_core::decap` failed - typically an invalid witness.
+    #[error("Decap failed: {0}")]
+    DecapFailed(String),
+
+    /// A symmetric-encryption, key (de)serialization, or armor/hybrid-KEM
+    /// failure.
+    #[error("Cryptographic operation failed: {0}")]
+    Crypto(String),
+}
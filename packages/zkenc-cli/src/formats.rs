@@ -0,0 +1,226 @@
+// formats.rs - Human-readable output encodings for ciphertext/key artifacts
+//
+// `encap_command`/`encrypt_command` et al. serialize `Ciphertext<Bn254>`/
+// `Key` via ark-serialize's compressed binary form by default. This module
+// adds two more encodings of the same bytes, following the pattern of
+// layering serde and serde_json alongside canonical binary serialization:
+// `Hex` writes the compressed bytes as a hex string, and `Json` wraps them
+// in a small serde envelope so the output stays inspectable and pipeable
+// into other tooling without losing the canonical encoding underneath.
+
+use anyhow::{Context, Result};
+use ark_bn254::Bn254;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{Deserialize, Serialize};
+use zkenc_core::{Ciphertext, Key};
+
+/// Output/input encoding for a ciphertext or key artifact, selected via
+/// `--format`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Canonical `serialize_compressed` bytes, written as-is.
+    Binary,
+    /// The compressed bytes, hex-encoded as ASCII text.
+    Hex,
+    /// The compressed bytes, hex-encoded and wrapped in a small JSON
+    /// envelope (see [`CiphertextEnvelope`]/[`KeyEnvelope`]).
+    Json,
+}
+
+/// Envelope format version, bumped if the envelope's shape changes.
+/// Unrelated to any other version byte this crate writes elsewhere (e.g.
+/// `codec::CombinedCiphertext`'s encryption-mode `version`).
+const ENVELOPE_VERSION: u8 = 1;
+
+/// serde envelope for a JSON-formatted ciphertext artifact: `{ "version":
+/// 1, "ciphertext": "<hex>" }`. `ciphertext` is the same bytes
+/// `Ciphertext::serialize_compressed` (or, for the combined-ciphertext
+/// container, `codec::CombinedCiphertext::encode`) produces, hex-encoded -
+/// this is a thin human-readable layer over the canonical encoding, not a
+/// reimplementation of it.
+#[derive(Serialize, Deserialize)]
+struct CiphertextEnvelope {
+    version: u8,
+    ciphertext: String,
+}
+
+/// serde envelope for a JSON-formatted key artifact: `{ "version": 1,
+/// "key": "<hex>" }`.
+#[derive(Serialize, Deserialize)]
+struct KeyEnvelope {
+    version: u8,
+    key: String,
+}
+
+/// Encode `ciphertext` in `format`.
+pub fn encode_ciphertext(ciphertext: &Ciphertext<Bn254>, format: OutputFormat) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    ciphertext
+        .serialize_compressed(&mut bytes)
+        .context("Failed to serialize ciphertext")?;
+    encode_ciphertext_bytes(bytes, format)
+}
+
+/// Encode already-`serialize_compressed`d (or otherwise canonically
+/// encoded) ciphertext bytes in `format`. Used both by
+/// [`encode_ciphertext`] and for the combined-ciphertext container (see
+/// [`crate::codec::CombinedCiphertext`]), which is itself a ciphertext
+/// artifact but not a `Ciphertext<Bn254>` value.
+pub fn encode_ciphertext_bytes(bytes: Vec<u8>, format: OutputFormat) -> Result<Vec<u8>> {
+    match format {
+        OutputFormat::Binary => Ok(bytes),
+        OutputFormat::Hex => Ok(hex::encode(bytes).into_bytes()),
+        OutputFormat::Json => {
+            let envelope = CiphertextEnvelope {
+                version: ENVELOPE_VERSION,
+                ciphertext: hex::encode(bytes),
+            };
+            serde_json::to_vec_pretty(&envelope).context("Failed to serialize ciphertext envelope")
+        }
+    }
+}
+
+/// Decode a ciphertext artifact written by [`encode_ciphertext`] in `format`.
+pub fn decode_ciphertext(data: &[u8], format: OutputFormat) -> Result<Ciphertext<Bn254>> {
+    let bytes = decode_ciphertext_bytes(data, format)?;
+    Ciphertext::<Bn254>::deserialize_compressed(&bytes[..])
+        .context("Failed to deserialize ciphertext")
+}
+
+/// Decode a ciphertext artifact back to its raw canonically-encoded bytes,
+/// undoing whichever of [`OutputFormat`]'s encodings was used.
+pub fn decode_ciphertext_bytes(data: &[u8], format: OutputFormat) -> Result<Vec<u8>> {
+    match format {
+        OutputFormat::Binary => Ok(data.to_vec()),
+        OutputFormat::Hex => {
+            let text = std::str::from_utf8(data).context("Hex ciphertext file is not valid UTF-8")?;
+            hex::decode(text.trim()).context("Failed to decode hex ciphertext")
+        }
+        OutputFormat::Json => {
+            let text = std::str::from_utf8(data).context("JSON ciphertext file is not valid UTF-8")?;
+            let envelope: CiphertextEnvelope =
+                serde_json::from_str(text).context("Failed to parse ciphertext envelope JSON")?;
+            if envelope.version != ENVELOPE_VERSION {
+                anyhow::bail!(
+                    "Unsupported ciphertext envelope version: {}",
+                    envelope.version
+                );
+            }
+            hex::decode(&envelope.ciphertext)
+                .context("Ciphertext envelope's hex field is not valid hex")
+        }
+    }
+}
+
+/// Encode `key` in `format`.
+pub fn encode_key(key: &Key, format: OutputFormat) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    key.serialize_compressed(&mut bytes)
+        .context("Failed to serialize key")?;
+
+    match format {
+        OutputFormat::Binary => Ok(bytes),
+        OutputFormat::Hex => Ok(hex::encode(bytes).into_bytes()),
+        OutputFormat::Json => {
+            let envelope = KeyEnvelope {
+                version: ENVELOPE_VERSION,
+                key: hex::encode(bytes),
+            };
+            serde_json::to_vec_pretty(&envelope).context("Failed to serialize key envelope")
+        }
+    }
+}
+
+/// Decode a key artifact written by [`encode_key`] in `format`.
+pub fn decode_key(data: &[u8], format: OutputFormat) -> Result<Key> {
+    let bytes = match format {
+        OutputFormat::Binary => data.to_vec(),
+        OutputFormat::Hex => {
+            let text = std::str::from_utf8(data).context("Hex key file is not valid UTF-8")?;
+            hex::decode(text.trim()).context("Failed to decode hex key")?
+        }
+        OutputFormat::Json => {
+            let text = std::str::from_utf8(data).context("JSON key file is not valid UTF-8")?;
+            let envelope: KeyEnvelope =
+                serde_json::from_str(text).context("Failed to parse key envelope JSON")?;
+            if envelope.version != ENVELOPE_VERSION {
+                anyhow::bail!("Unsupported key envelope version: {}", envelope.version);
+            }
+            hex::decode(&envelope.key).context("Key envelope's hex field is not valid hex")?
+        }
+    };
+
+    Key::deserialize_compressed(&bytes[..]).context("Failed to deserialize key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_key() -> Key {
+        Key::new([0x42; 32])
+    }
+
+    #[test]
+    fn test_key_roundtrip_binary() {
+        let key = sample_key();
+        let encoded = encode_key(&key, OutputFormat::Binary).unwrap();
+        let decoded = decode_key(&encoded, OutputFormat::Binary).unwrap();
+        assert_eq!(decoded, key);
+    }
+
+    #[test]
+    fn test_key_roundtrip_hex() {
+        let key = sample_key();
+        let encoded = encode_key(&key, OutputFormat::Hex).unwrap();
+        assert!(std::str::from_utf8(&encoded)
+            .unwrap()
+            .chars()
+            .all(|c| c.is_ascii_hexdigit()));
+        let decoded = decode_key(&encoded, OutputFormat::Hex).unwrap();
+        assert_eq!(decoded, key);
+    }
+
+    #[test]
+    fn test_key_roundtrip_json() {
+        let key = sample_key();
+        let encoded = encode_key(&key, OutputFormat::Json).unwrap();
+        let text = std::str::from_utf8(&encoded).unwrap();
+        assert!(text.contains("\"version\""));
+        assert!(text.contains("\"key\""));
+        let decoded = decode_key(&encoded, OutputFormat::Json).unwrap();
+        assert_eq!(decoded, key);
+    }
+
+    #[test]
+    fn test_key_json_rejects_unsupported_version() {
+        let json = r#"{"version": 99, "key": "00"}"#;
+        let err = decode_key(json.as_bytes(), OutputFormat::Json).unwrap_err();
+        assert!(err.to_string().contains("Unsupported key envelope version"));
+    }
+
+    #[test]
+    fn test_ciphertext_bytes_roundtrip_hex() {
+        let original = b"not a real ciphertext, just some bytes".to_vec();
+        let encoded = encode_ciphertext_bytes(original.clone(), OutputFormat::Hex).unwrap();
+        let decoded = decode_ciphertext_bytes(&encoded, OutputFormat::Hex).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_ciphertext_bytes_roundtrip_json() {
+        let original = b"not a real ciphertext, just some bytes".to_vec();
+        let encoded = encode_ciphertext_bytes(original.clone(), OutputFormat::Json).unwrap();
+        let decoded = decode_ciphertext_bytes(&encoded, OutputFormat::Json).unwrap();
+        assert_eq!(decoded, original);
+    }
+
+    #[test]
+    fn test_ciphertext_bytes_json_rejects_unsupported_version() {
+        let json = r#"{"version": 7, "ciphertext": "00"}"#;
+        let err = decode_ciphertext_bytes(json.as_bytes(), OutputFormat::Json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported ciphertext envelope version"));
+    }
+}
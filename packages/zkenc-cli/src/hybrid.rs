@@ -0,0 +1,234 @@
+// hybrid.rs - X-Wing-style hybrid KEM combiner
+//
+// Combines zkenc's witness-KEM with a classical KEM (X25519, via an
+// ephemeral-static Diffie-Hellman "KEM") so the derived key stays secure
+// even if the pairing assumptions underlying the witness-KEM are broken in
+// the future: an attacker needs both the witness *and* the X25519 secret
+// key to recover the symmetric key, not just one.
+//
+// Final key = Blake3-derive_key(label, ss_wkem || ss_ext || ct_ext || pk_ext),
+// where ss_wkem is the witness-KEM's derived `Key` (used as Blake3 keyed
+// input material, not the final key itself), ct_ext is the X25519
+// ephemeral public key, and pk_ext is the recipient's long-term X25519
+// public key.
+
+use anyhow::{bail, Context, Result};
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_relations::gr1cs::ConstraintSynthesizer;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use x25519_dalek::{EphemeralSecret, PublicKey, StaticSecret};
+use zkenc_core::{decap, encap, Ciphertext, Key};
+
+/// Domain-separation label fed into Blake3's `derive_key` mode.
+const HYBRID_KDF_LABEL: &str = "zkenc-hybrid-key-v1";
+
+/// Combined ciphertext: the witness-KEM ciphertext, the X25519 ephemeral
+/// public key (`ct_ext`), and the recipient's long-term X25519 public key
+/// (`pk_ext`), carried for self-description.
+///
+/// # Format
+/// `[wkem_len(4 bytes BE)][wkem ciphertext][ct_ext(32 bytes)][pk_ext(32 bytes)]`
+pub struct HybridCiphertext {
+    pub wkem_ciphertext: Ciphertext<Bn254>,
+    pub ct_ext: [u8; 32],
+    pub pk_ext: [u8; 32],
+}
+
+impl HybridCiphertext {
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut wkem_bytes = Vec::new();
+        self.wkem_ciphertext
+            .serialize_compressed(&mut wkem_bytes)
+            .context("Failed to serialize witness-KEM ciphertext")?;
+
+        let mut out = Vec::with_capacity(4 + wkem_bytes.len() + 64);
+        out.extend_from_slice(&(wkem_bytes.len() as u32).to_be_bytes());
+        out.extend_from_slice(&wkem_bytes);
+        out.extend_from_slice(&self.ct_ext);
+        out.extend_from_slice(&self.pk_ext);
+        Ok(out)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        if data.len() < 4 {
+            bail!("Hybrid ciphertext too short: missing witness-KEM length header");
+        }
+        let wkem_len = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+        let wkem_end = 4 + wkem_len;
+        if data.len() != wkem_end + 64 {
+            bail!(
+                "Hybrid ciphertext has the wrong length: expected {} bytes, got {}",
+                wkem_end + 64,
+                data.len()
+            );
+        }
+
+        let wkem_ciphertext = Ciphertext::<Bn254>::deserialize_compressed(&data[4..wkem_end])
+            .context("Failed to deserialize witness-KEM ciphertext")?;
+
+        let mut ct_ext = [0u8; 32];
+        ct_ext.copy_from_slice(&data[wkem_end..wkem_end + 32]);
+        let mut pk_ext = [0u8; 32];
+        pk_ext.copy_from_slice(&data[wkem_end + 32..wkem_end + 64]);
+
+        Ok(Self {
+            wkem_ciphertext,
+            ct_ext,
+            pk_ext,
+        })
+    }
+}
+
+fn derive_hybrid_key(ss_wkem: &Key, ss_ext: &[u8; 32], ct_ext: &[u8; 32], pk_ext: &[u8; 32]) -> Key {
+    let mut ikm = Vec::with_capacity(32 + 32 + 32 + 32);
+    ikm.extend_from_slice(ss_wkem.as_bytes());
+    ikm.extend_from_slice(ss_ext);
+    ikm.extend_from_slice(ct_ext);
+    ikm.extend_from_slice(pk_ext);
+
+    Key::new(blake3::derive_key(HYBRID_KDF_LABEL, &ikm))
+}
+
+/// Encapsulate under both the witness-KEM (`circuit`, public inputs only)
+/// and X25519 (`recipient_public_key`), returning a [`HybridCiphertext`]
+/// and the combined [`Key`].
+pub fn encap_hybrid<C, R>(
+    circuit: C,
+    recipient_public_key: &PublicKey,
+    rng: &mut R,
+) -> Result<(HybridCiphertext, Key)>
+where
+    C: ConstraintSynthesizer<<Bn254 as Pairing>::ScalarField>,
+    R: RngCore,
+{
+    let (wkem_ciphertext, ss_wkem) = encap::<Bn254, _, _>(circuit, rng)
+        .map_err(|e| anyhow::anyhow!("Witness-KEM encap failed: {:?}", e))?;
+
+    // x25519-dalek's RNG trait predates `ark_std::rand`'s; bridge by
+    // drawing the ephemeral scalar's bytes through the caller's `rng`.
+    let mut ephemeral_bytes = [0u8; 32];
+    rng.fill_bytes(&mut ephemeral_bytes);
+    let ephemeral_secret = EphemeralSecret::from(ephemeral_bytes);
+    let ct_ext = PublicKey::from(&ephemeral_secret).to_bytes();
+    let ss_ext = ephemeral_secret.diffie_hellman(recipient_public_key).to_bytes();
+    let pk_ext = recipient_public_key.to_bytes();
+
+    let key = derive_hybrid_key(&ss_wkem, &ss_ext, &ct_ext, &pk_ext);
+
+    Ok((
+        HybridCiphertext {
+            wkem_ciphertext,
+            ct_ext,
+            pk_ext,
+        },
+        key,
+    ))
+}
+
+/// Decapsulate a [`HybridCiphertext`]: both `circuit`'s witness and
+/// `recipient_secret_key` must be correct, or this fails. The recipient's
+/// X25519 public key is recomputed from `recipient_secret_key` rather than
+/// trusted from the (attacker-controlled) ciphertext field, so a
+/// ciphertext encapsulated for a different recipient is rejected instead
+/// of silently deriving a key no one else can reproduce.
+pub fn decap_hybrid<C>(
+    circuit: C,
+    ciphertext: &HybridCiphertext,
+    recipient_secret_key: &StaticSecret,
+) -> Result<Key>
+where
+    C: ConstraintSynthesizer<<Bn254 as Pairing>::ScalarField>,
+{
+    let ss_wkem = decap::<Bn254, _>(circuit, &ciphertext.wkem_ciphertext)
+        .map_err(|e| anyhow::anyhow!("Witness-KEM decap failed: {:?}", e))?;
+
+    let pk_ext = PublicKey::from(recipient_secret_key).to_bytes();
+    if pk_ext != ciphertext.pk_ext {
+        bail!("Hybrid ciphertext was not encapsulated for this X25519 key pair");
+    }
+
+    let ct_ext = PublicKey::from(ciphertext.ct_ext);
+    let ss_ext = recipient_secret_key.diffie_hellman(&ct_ext).to_bytes();
+
+    Ok(derive_hybrid_key(&ss_wkem, &ss_ext, &ciphertext.ct_ext, &pk_ext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_relations::gr1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    /// Trivial always-satisfied circuit, just to exercise the hybrid
+    /// combiner without pulling in a real R1CS fixture.
+    #[derive(Clone)]
+    struct TrivialCircuit;
+
+    impl ConstraintSynthesizer<Fr> for TrivialCircuit {
+        fn generate_constraints(self, _cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_hybrid_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let recipient_secret = StaticSecret::from({
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        });
+        let recipient_public = PublicKey::from(&recipient_secret);
+
+        let (ciphertext, key1) =
+            encap_hybrid(TrivialCircuit, &recipient_public, &mut rng).unwrap();
+        let key2 = decap_hybrid(TrivialCircuit, &ciphertext, &recipient_secret).unwrap();
+
+        assert_eq!(key1, key2);
+    }
+
+    #[test]
+    fn test_hybrid_wrong_secret_key_fails() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let recipient_secret = StaticSecret::from({
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        });
+        let recipient_public = PublicKey::from(&recipient_secret);
+
+        let (ciphertext, _key1) =
+            encap_hybrid(TrivialCircuit, &recipient_public, &mut rng).unwrap();
+
+        let wrong_secret = StaticSecret::from({
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        });
+
+        assert!(decap_hybrid(TrivialCircuit, &ciphertext, &wrong_secret).is_err());
+    }
+
+    #[test]
+    fn test_hybrid_ciphertext_bytes_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let recipient_secret = StaticSecret::from({
+            let mut bytes = [0u8; 32];
+            rng.fill_bytes(&mut bytes);
+            bytes
+        });
+        let recipient_public = PublicKey::from(&recipient_secret);
+
+        let (ciphertext, _key) =
+            encap_hybrid(TrivialCircuit, &recipient_public, &mut rng).unwrap();
+
+        let bytes = ciphertext.to_bytes().unwrap();
+        let parsed = HybridCiphertext::from_bytes(&bytes).unwrap();
+
+        assert_eq!(parsed.ct_ext, ciphertext.ct_ext);
+        assert_eq!(parsed.pk_ext, ciphertext.pk_ext);
+    }
+}
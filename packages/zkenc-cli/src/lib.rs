@@ -3,9 +3,16 @@
 
 pub mod circom;
 pub mod circuit;
+pub mod codec;
 pub mod commands;
 pub mod crypto;
+pub mod envelope;
+pub mod error;
+pub mod formats;
+pub mod hybrid;
 pub mod r1cs;
 pub mod serializable;
+pub mod shamir;
 pub mod sym_parser;
 pub mod witness;
+pub mod witness_calculator;
@@ -10,6 +10,26 @@ use zkenc_cli::witness;
 mod commands;
 mod sym_parser;
 
+/// CLI-facing mirror of [`zkenc_cli::formats::OutputFormat`] - defined
+/// locally (rather than deriving `clap::ValueEnum` on the library type
+/// directly) so the library crate doesn't need to depend on clap.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+enum OutputFormatArg {
+    Binary,
+    Hex,
+    Json,
+}
+
+impl From<OutputFormatArg> for zkenc_cli::formats::OutputFormat {
+    fn from(value: OutputFormatArg) -> Self {
+        match value {
+            OutputFormatArg::Binary => Self::Binary,
+            OutputFormatArg::Hex => Self::Hex,
+            OutputFormatArg::Json => Self::Json,
+        }
+    }
+}
+
 /// zkenc CLI - Zero-Knowledge Encryption Tool
 #[derive(Parser)]
 #[command(name = "zkenc")]
@@ -38,21 +58,53 @@ enum Commands {
         /// Output key file path
         #[arg(short, long)]
         key: String,
+        /// Recipient's X25519 public key file (32 raw bytes) - when set,
+        /// the key is also hybrid-wrapped so it stays secure if the
+        /// witness-KEM's pairing assumptions are ever broken
+        #[arg(long)]
+        hybrid_recipient_pubkey: Option<String>,
+        /// Write the ciphertext and key as OpenPGP-style ASCII-armored text
+        /// instead of raw bytes, so they survive copy/paste or email
+        #[arg(long, default_value = "false")]
+        armor: bool,
+        /// Encoding for the ciphertext and key files: `binary` writes raw
+        /// bytes, `hex` writes a hex string, `json` wraps them in a
+        /// `{ "version", "ciphertext"|"key" }` envelope
+        #[arg(long, value_enum, default_value = "binary")]
+        format: OutputFormatArg,
     },
     /// Decapsulate: Recover key (using circuit and complete witness)
     Decap {
         /// R1CS circuit file path (.r1cs)
         #[arg(short, long)]
         circuit: String,
-        /// Witness file path (.wtns from snarkjs)
+        /// Witness file path (.wtns from snarkjs) - mutually exclusive with
+        /// --wasm/--input, which compute the witness in-memory instead
         #[arg(short, long)]
-        witness: String,
+        witness: Option<String>,
+        /// Circom `.wasm` witness generator - when set (together with
+        /// --input), the witness is computed directly from the circuit's
+        /// own WASM module instead of requiring a pre-built --witness file
+        #[arg(long)]
+        wasm: Option<String>,
+        /// Public+private input JSON file, required alongside --wasm
+        #[arg(long)]
+        input: Option<String>,
         /// Ciphertext file path
         #[arg(short, long)]
         ciphertext: String,
         /// Output key file path
         #[arg(short, long)]
         key: String,
+        /// Recipient's X25519 secret key file (32 raw bytes) - required to
+        /// decapsulate a ciphertext produced with --hybrid-recipient-pubkey
+        #[arg(long)]
+        hybrid_secret_key: Option<String>,
+        /// Encoding the ciphertext file was written in (must match
+        /// --format at encap time); the recovered key is written in the
+        /// same encoding
+        #[arg(long, value_enum, default_value = "binary")]
+        format: OutputFormatArg,
     },
     /// Encrypt: High-level encryption (compatible with zkenc-js format)
     Encrypt {
@@ -74,6 +126,25 @@ enum Commands {
         /// Do not include public input in ciphertext (default: includes it)
         #[arg(long, default_value = "false")]
         no_public_input: bool,
+        /// Seal the message with AES-256-GCM, binding the public inputs as
+        /// associated data so tampering with them fails authentication
+        #[arg(long, default_value = "false")]
+        aead: bool,
+        /// Treat the message as a JSON array of scalar field elements and
+        /// seal it with the Poseidon duplex cipher instead of AES-GCM, so
+        /// the ciphertext stays inside the curve's scalar field. Cannot be
+        /// combined with --aead.
+        #[arg(long, default_value = "false")]
+        field: bool,
+        /// Write the combined ciphertext as OpenPGP-style ASCII-armored text
+        /// instead of raw bytes, so it survives copy/paste or email
+        #[arg(long, default_value = "false")]
+        armor: bool,
+        /// Encoding for the combined ciphertext file: `binary` writes raw
+        /// bytes, `hex` writes a hex string, `json` wraps them in a
+        /// `{ "version", "ciphertext" }` envelope
+        #[arg(long, value_enum, default_value = "binary")]
+        format: OutputFormatArg,
     },
     /// Decrypt: High-level decryption (compatible with zkenc-js format)
     Decrypt {
@@ -89,6 +160,45 @@ enum Commands {
         /// Output decrypted message file
         #[arg(short, long)]
         output: String,
+        /// Expect the combined ciphertext to be in `--aead` mode (must
+        /// match the mode used when the ciphertext was produced)
+        #[arg(long, default_value = "false")]
+        aead: bool,
+        /// Expect the combined ciphertext to be in `--field` mode (must
+        /// match the mode used when the ciphertext was produced)
+        #[arg(long, default_value = "false")]
+        field: bool,
+        /// Encoding the combined ciphertext file was written in (must
+        /// match --format at encrypt time)
+        #[arg(long, value_enum, default_value = "binary")]
+        format: OutputFormatArg,
+    },
+    /// Share-key: split a key_encap.bin/key_decap.bin into Shamir shares
+    ShareKey {
+        /// Key file to split (e.g. key_encap.bin)
+        #[arg(short, long)]
+        key: String,
+        /// Number of shares required to reconstruct the key
+        #[arg(short, long)]
+        threshold: usize,
+        /// Total number of shares to generate
+        #[arg(short, long)]
+        shares: usize,
+        /// Output directory for the generated share files
+        #[arg(short, long)]
+        output: String,
+    },
+    /// Combine-key: reconstruct a key from Shamir shares produced by share-key
+    CombineKey {
+        /// Share files to combine (at least `threshold` of them)
+        #[arg(short, long, num_args = 1.., required = true)]
+        shares: Vec<String>,
+        /// Number of shares required to reconstruct the key
+        #[arg(short, long)]
+        threshold: usize,
+        /// Output path for the reconstructed key file
+        #[arg(short, long)]
+        output: String,
     },
 }
 
@@ -102,16 +212,41 @@ fn main() -> Result<()> {
             input,
             ciphertext,
             key,
+            hybrid_recipient_pubkey,
+            armor,
+            format,
         } => {
-            commands::encap_command(&circuit, &sym, &input, &ciphertext, &key)?;
+            commands::encap_command(
+                &circuit,
+                &sym,
+                &input,
+                &ciphertext,
+                &key,
+                hybrid_recipient_pubkey.as_deref(),
+                armor,
+                format.into(),
+            )?;
         }
         Commands::Decap {
             circuit,
             witness,
+            wasm,
+            input,
             ciphertext,
             key,
+            hybrid_secret_key,
+            format,
         } => {
-            commands::decap_command(&circuit, &witness, &ciphertext, &key)?;
+            commands::decap_command(
+                &circuit,
+                witness.as_deref(),
+                wasm.as_deref(),
+                input.as_deref(),
+                &ciphertext,
+                &key,
+                hybrid_secret_key.as_deref(),
+                format.into(),
+            )?;
         }
 
         Commands::Encrypt {
@@ -121,16 +256,57 @@ fn main() -> Result<()> {
             message,
             output,
             no_public_input,
+            aead,
+            field,
+            armor,
+            format,
         } => {
-            commands::encrypt_command(&circuit, &sym, &input, &message, &output, !no_public_input)?;
+            commands::encrypt_command(
+                &circuit,
+                &sym,
+                &input,
+                &message,
+                &output,
+                !no_public_input,
+                aead,
+                field,
+                armor,
+                format.into(),
+            )?;
         }
         Commands::Decrypt {
             circuit,
             witness,
             ciphertext,
             output,
+            aead,
+            field,
+            format,
+        } => {
+            commands::decrypt_command(
+                &circuit,
+                &witness,
+                &ciphertext,
+                &output,
+                aead,
+                field,
+                format.into(),
+            )?;
+        }
+        Commands::ShareKey {
+            key,
+            threshold,
+            shares,
+            output,
+        } => {
+            commands::share_key_command(&key, threshold, shares, &output)?;
+        }
+        Commands::CombineKey {
+            shares,
+            threshold,
+            output,
         } => {
-            commands::decrypt_command(&circuit, &witness, &ciphertext, &output)?;
+            commands::combine_key_command(&shares, threshold, &output)?;
         }
     }
 
@@ -1,4 +1,10 @@
 use anyhow::{bail, Context, Result};
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::{
+    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination as ArkLinearCombination,
+    SynthesisError, Variable, R1CS_PREDICATE_LABEL,
+};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, Read, Seek};
 use std::path::Path;
@@ -10,7 +16,16 @@ use crate::serializable::{
 ///
 /// Parses Circom R1CS binary format according to:
 /// https://github.com/iden3/r1csfile/blob/master/doc/r1cs_bin_format.md
-#[derive(Debug)]
+///
+/// This is the direct snarkjs `.r1cs` loader: magic check, version check,
+/// then section-type dispatch (header `0x01`, constraints `0x02`, wire2label
+/// `0x03`, with the v2-only custom-gate sections `0x04`/`0x05` skipped when
+/// absent), the same shape `WitnessFile::from_file` in `witness.rs` uses for
+/// `.wtns`. [`R1csFile::to_serializable`] hands back the exact
+/// `SerializableCircuit` a bincode fixture would, so a circom/snarkjs
+/// `.r1cs` file can be fed straight into the rest of this crate without a
+/// separate conversion step.
+#[derive(Debug, Clone)]
 pub struct R1csFile {
     pub field_size: u32,
     pub prime: Vec<u8>,
@@ -22,6 +37,13 @@ pub struct R1csFile {
     pub n_constraints: u32,
     pub constraints: Vec<Constraint>,
     pub wire2label: Vec<u64>,
+    /// Custom gate templates declared by the "custom gates used list"
+    /// section (v2 only; empty for v1 files or v2 files without custom gates).
+    pub custom_gates: Vec<CustomGate>,
+    /// Per-application bindings from the "custom gates applied" section,
+    /// mapping each use of a custom gate to the wires it was applied to
+    /// (v2 only; empty for v1 files or v2 files without custom gates).
+    pub custom_gate_applications: Vec<CustomGateApplication>,
 }
 
 #[derive(Debug, Clone)]
@@ -36,6 +58,23 @@ pub struct LinearCombination {
     pub factors: Vec<(u32, Vec<u8>)>, // (wire_id, value in little-endian)
 }
 
+/// A custom gate template, as declared by the v2 "custom gates used list" section.
+#[derive(Debug, Clone)]
+pub struct CustomGate {
+    pub template_name: String,
+    /// Template parameters, each a field element in little-endian bytes.
+    pub parameters: Vec<Vec<u8>>,
+}
+
+/// A single use of a custom gate, as declared by the v2 "custom gates
+/// applied" section: which template (`custom_gate_id`, indexing into
+/// [`R1csFile::custom_gates`]) was applied to which wires.
+#[derive(Debug, Clone)]
+pub struct CustomGateApplication {
+    pub custom_gate_id: u32,
+    pub signals: Vec<u32>,
+}
+
 impl R1csFile {
     /// Convert to SerializableCircuit for testing/export
     pub fn to_serializable(&self) -> SerializableCircuit {
@@ -79,6 +118,24 @@ impl R1csFile {
             })
             .collect();
 
+        let custom_gates = self
+            .custom_gates
+            .iter()
+            .map(|g| SerializableCustomGate {
+                template_name: g.template_name.clone(),
+                parameters: g.parameters.clone(),
+            })
+            .collect();
+
+        let custom_gate_applications = self
+            .custom_gate_applications
+            .iter()
+            .map(|a| SerializableCustomGateApplication {
+                custom_gate_id: a.custom_gate_id,
+                signals: a.signals.clone(),
+            })
+            .collect();
+
         SerializableCircuit {
             field_size: self.field_size,
             prime_bytes: self.prime.clone(),
@@ -89,10 +146,18 @@ impl R1csFile {
             n_constraints: self.n_constraints,
             constraints,
             wire_labels: None, // We don't parse labels for now
+            custom_gates,
+            custom_gate_applications,
         }
     }
 
     /// Parse an R1CS file from disk
+    ///
+    /// Accepts both version 1 and version 2 files. Version 2 adds the
+    /// "custom gates used list" (`0x04`) and "custom gates applied" (`0x05`)
+    /// sections for circuits built from non-R1CS gates; a v2 file that omits
+    /// them behaves exactly like a v1 file (`custom_gates` and
+    /// `custom_gate_applications` are left empty).
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let file = File::open(path.as_ref())
             .with_context(|| format!("Failed to open R1CS file: {:?}", path.as_ref()))?;
@@ -105,9 +170,9 @@ impl R1csFile {
             bail!("Invalid R1CS file: wrong magic number {:?}", magic);
         }
 
-        // Parse version (must be 1)
+        // Parse version (1 or 2)
         let version = read_u32(&mut reader)?;
-        if version != 1 {
+        if version != 1 && version != 2 {
             bail!("Unsupported R1CS version: {}", version);
         }
 
@@ -158,6 +223,24 @@ impl R1csFile {
                 (0..header.n_wires as u64).collect()
             };
 
+        // Parse custom gates used list section (v2 only, optional)
+        let custom_gates =
+            if let Some(section) = sections.iter().find(|(t, _, _)| *t == 0x04) {
+                reader.seek(std::io::SeekFrom::Start(section.2))?;
+                Self::parse_custom_gates_list(&mut reader, header.field_size as usize)?
+            } else {
+                Vec::new()
+            };
+
+        // Parse custom gates applied section (v2 only, optional)
+        let custom_gate_applications =
+            if let Some(section) = sections.iter().find(|(t, _, _)| *t == 0x05) {
+                reader.seek(std::io::SeekFrom::Start(section.2))?;
+                Self::parse_custom_gate_applications(&mut reader, header.n_constraints as usize)?
+            } else {
+                Vec::new()
+            };
+
         Ok(R1csFile {
             field_size: header.field_size,
             prime: header.prime,
@@ -169,13 +252,25 @@ impl R1csFile {
             n_constraints: header.n_constraints,
             constraints,
             wire2label,
+            custom_gates,
+            custom_gate_applications,
         })
     }
 
     fn parse_header(reader: &mut BufReader<File>) -> Result<R1csHeader> {
         let field_size = read_u32(reader)?;
-        let mut prime = vec![0u8; field_size as usize];
-        reader.read_exact(&mut prime)?;
+        // field_size is an untrusted u32 straight off the file; same reasoning
+        // as parse_custom_gates_list - read it incrementally instead of
+        // `vec![0u8; field_size]` so a crafted header can't force a
+        // multi-gigabyte allocation before a single prime byte is checked.
+        let mut prime = Vec::new();
+        let read = reader
+            .take(field_size as u64)
+            .read_to_end(&mut prime)
+            .context("Failed to read field prime")?;
+        if read != field_size as usize {
+            bail!("Unexpected end of data reading field prime");
+        }
 
         let n_wires = read_u32(reader)?;
         let n_pub_out = read_u32(reader)?;
@@ -201,7 +296,9 @@ impl R1csFile {
         n_constraints: usize,
         field_size: usize,
     ) -> Result<Vec<Constraint>> {
-        let mut constraints = Vec::with_capacity(n_constraints);
+        // n_constraints is an untrusted u32 from the header; same reasoning as
+        // parse_custom_gates_list - don't pre-allocate capacity for it up front.
+        let mut constraints = Vec::new();
 
         for _ in 0..n_constraints {
             let a = Self::parse_lc(reader, field_size)?;
@@ -216,12 +313,19 @@ impl R1csFile {
 
     fn parse_lc(reader: &mut BufReader<File>, field_size: usize) -> Result<LinearCombination> {
         let n_factors = read_u32(reader)?;
-        let mut factors = Vec::with_capacity(n_factors as usize);
+        // n_factors is untrusted, same reasoning as parse_custom_gates_list.
+        let mut factors = Vec::new();
 
         for _ in 0..n_factors {
             let wire_id = read_u32(reader)?;
-            let mut value = vec![0u8; field_size];
-            reader.read_exact(&mut value)?;
+            let mut value = Vec::new();
+            let read = reader
+                .take(field_size as u64)
+                .read_to_end(&mut value)
+                .context("Failed to read linear combination factor value")?;
+            if read != field_size {
+                bail!("Unexpected end of data reading linear combination factor value");
+            }
             factors.push((wire_id, value));
         }
 
@@ -229,17 +333,216 @@ impl R1csFile {
     }
 
     fn parse_wire2label(reader: &mut BufReader<File>, n_wires: usize) -> Result<Vec<u64>> {
-        let mut wire2label = Vec::with_capacity(n_wires);
+        // n_wires is an untrusted u32 from the header; same reasoning as
+        // parse_custom_gates_list - don't pre-allocate capacity for it up front.
+        let mut wire2label = Vec::new();
         for _ in 0..n_wires {
             wire2label.push(read_u64(reader)?);
         }
         Ok(wire2label)
     }
 
+    /// Parse the v2 "custom gates used list" section: a count followed by,
+    /// for each template, a length-prefixed name and a length-prefixed list
+    /// of field-element parameters.
+    fn parse_custom_gates_list(
+        reader: &mut BufReader<File>,
+        field_size: usize,
+    ) -> Result<Vec<CustomGate>> {
+        let n_custom_gates = read_u32(reader)?;
+        // n_custom_gates/name_len/n_params are untrusted u32s straight off the
+        // file; a crafted section can claim e.g. n_custom_gates = u32::MAX to
+        // make an upfront Vec::with_capacity/vec![0u8; ...] try to allocate
+        // gigabytes before a single body byte is checked. Growing these
+        // incrementally instead means a truncated/malicious file fails on the
+        // read_exact/read_to_end for the next element, not on allocation.
+        let mut custom_gates = Vec::new();
+
+        for _ in 0..n_custom_gates {
+            let name_len = read_u32(reader)?;
+            let mut name_bytes = Vec::new();
+            let read = reader
+                .take(name_len as u64)
+                .read_to_end(&mut name_bytes)
+                .context("Failed to read custom gate template name")?;
+            if read != name_len as usize {
+                bail!("Unexpected end of data reading custom gate template name");
+            }
+            let template_name = String::from_utf8(name_bytes)
+                .context("Custom gate template name is not valid UTF-8")?;
+
+            let n_params = read_u32(reader)?;
+            let mut parameters = Vec::new();
+            for _ in 0..n_params {
+                let mut param = Vec::new();
+                let read = reader
+                    .take(field_size as u64)
+                    .read_to_end(&mut param)
+                    .context("Failed to read custom gate parameter")?;
+                if read != field_size {
+                    bail!("Unexpected end of data reading custom gate parameter");
+                }
+                parameters.push(param);
+            }
+
+            custom_gates.push(CustomGate {
+                template_name,
+                parameters,
+            });
+        }
+
+        Ok(custom_gates)
+    }
+
+    /// Parse the v2 "custom gates applied" section: a count followed by,
+    /// for each application, the custom gate's index into the "used list"
+    /// section and the wires it was applied to.
+    fn parse_custom_gate_applications(
+        reader: &mut BufReader<File>,
+        n_constraints: usize,
+    ) -> Result<Vec<CustomGateApplication>> {
+        let n_applications = read_u32(reader)?;
+        let mut applications = Vec::with_capacity(n_applications.min(n_constraints as u32) as usize);
+
+        for _ in 0..n_applications {
+            let custom_gate_id = read_u32(reader)?;
+            let n_signals = read_u32(reader)?;
+            // Same reasoning as parse_custom_gates_list: n_signals is
+            // untrusted, so don't pre-allocate capacity for it up front.
+            let mut signals = Vec::new();
+            for _ in 0..n_signals {
+                signals.push(read_u32(reader)?);
+            }
+            applications.push(CustomGateApplication {
+                custom_gate_id,
+                signals,
+            });
+        }
+
+        Ok(applications)
+    }
+
     /// Get the number of public inputs (outputs + inputs)
     pub fn n_public_inputs(&self) -> u32 {
         self.n_pub_out + self.n_pub_in
     }
+
+    /// Bridge this parsed R1CS into an `ark_relations` `ConstraintSynthesizer<F>`,
+    /// ready to drive `zkenc_core::encap`/`decap` directly from a `.r1cs` file.
+    ///
+    /// Checks that `self.prime` matches `F`'s modulus before touching any
+    /// witness data, so e.g. a BN254 R1CS file cannot silently be loaded
+    /// against BLS12-381's scalar field.
+    ///
+    /// `witness` is optional and, when present, must hold one little-endian
+    /// field-element entry per wire (wire 0 is the constant `1`): pass `None`
+    /// to build a public-inputs-only assignment for `encap`, or the full
+    /// per-wire witness for a fully assigned circuit for `decap`.
+    pub fn into_synthesizer<F: PrimeField>(
+        &self,
+        witness: Option<&[Vec<u8>]>,
+    ) -> Result<R1csSynthesizer<F>> {
+        let modulus = F::MODULUS.to_bytes_le();
+        if self.prime != modulus {
+            bail!(
+                "R1CS prime ({} bytes) does not match target field's modulus ({} bytes)",
+                self.prime.len(),
+                modulus.len()
+            );
+        }
+
+        let mut assignment = HashMap::new();
+        if let Some(values) = witness {
+            for (wire_id, bytes) in values.iter().enumerate() {
+                assignment.insert(wire_id as u32, F::from_le_bytes_mod_order(bytes));
+            }
+        }
+
+        Ok(R1csSynthesizer {
+            r1cs: self.clone(),
+            witness: assignment,
+        })
+    }
+}
+
+/// Field-generic bridge from a parsed [`R1csFile`] to `ark_relations`'
+/// `ConstraintSynthesizer`. Built via [`R1csFile::into_synthesizer`]; only
+/// the downstream `encap`/`decap` calls need a `Pairing`, so this only
+/// requires `F: PrimeField`.
+pub struct R1csSynthesizer<F: PrimeField> {
+    r1cs: R1csFile,
+    witness: HashMap<u32, F>,
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for R1csSynthesizer<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut variables: HashMap<u32, Variable> = HashMap::new();
+        variables.insert(0, Variable::One);
+
+        let n_public = self.r1cs.n_public_inputs();
+        for wire_id in 1..=n_public {
+            let value = self.witness.get(&wire_id).copied();
+            let var = cs.new_input_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+            variables.insert(wire_id, var);
+        }
+
+        for wire_id in (n_public + 1)..self.r1cs.n_wires {
+            let value = self.witness.get(&wire_id).copied();
+            let var = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+            variables.insert(wire_id, var);
+        }
+
+        for constraint in self.r1cs.constraints.iter() {
+            let a_factors = constraint.a.factors.clone();
+            let b_factors = constraint.b.factors.clone();
+            let c_factors = constraint.c.factors.clone();
+            let vars_a = variables.clone();
+            let vars_b = variables.clone();
+            let vars_c = variables.clone();
+
+            let a_closure = move || {
+                let mut lc = ArkLinearCombination::<F>::zero();
+                for (wire_id, coeff_bytes) in &a_factors {
+                    let coeff = F::from_le_bytes_mod_order(coeff_bytes);
+                    if let Some(var) = vars_a.get(wire_id) {
+                        lc = lc + (coeff, *var);
+                    }
+                }
+                lc
+            };
+
+            let b_closure = move || {
+                let mut lc = ArkLinearCombination::<F>::zero();
+                for (wire_id, coeff_bytes) in &b_factors {
+                    let coeff = F::from_le_bytes_mod_order(coeff_bytes);
+                    if let Some(var) = vars_b.get(wire_id) {
+                        lc = lc + (coeff, *var);
+                    }
+                }
+                lc
+            };
+
+            let c_closure = move || {
+                let mut lc = ArkLinearCombination::<F>::zero();
+                for (wire_id, coeff_bytes) in &c_factors {
+                    let coeff = F::from_le_bytes_mod_order(coeff_bytes);
+                    if let Some(var) = vars_c.get(wire_id) {
+                        lc = lc + (coeff, *var);
+                    }
+                }
+                lc
+            };
+
+            let boxed: Vec<Box<dyn FnOnce() -> ArkLinearCombination<F>>> = vec![
+                Box::new(a_closure),
+                Box::new(b_closure),
+                Box::new(c_closure),
+            ];
+            cs.enforce_constraint(R1CS_PREDICATE_LABEL, boxed)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -272,6 +575,33 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    #[test]
+    fn test_into_synthesizer_rejects_curve_mismatch() {
+        let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
+        let r1cs = R1csFile::from_file(&r1cs_path).expect("Failed to parse R1CS");
+
+        // signature.r1cs is a BN254 circuit; loading it against BLS12-381's
+        // scalar field must fail instead of silently misinterpreting coefficients.
+        let result = r1cs.into_synthesizer::<ark_bls12_381::Fr>(None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_into_synthesizer_public_only() {
+        let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
+        let r1cs = R1csFile::from_file(&r1cs_path).expect("Failed to parse R1CS");
+
+        let synthesizer = r1cs
+            .into_synthesizer::<ark_bn254::Fr>(None)
+            .expect("Matching curve should be accepted");
+
+        use ark_relations::gr1cs::ConstraintSystem;
+        let cs = ConstraintSystem::<ark_bn254::Fr>::new_ref();
+        // No witness supplied: synthesis fails on the first missing assignment,
+        // which is expected for a public-inputs-only adapter call.
+        assert!(synthesizer.generate_constraints(cs).is_err());
+    }
+
     #[test]
     fn test_parse_signature_r1cs() {
         let r1cs_path = PathBuf::from("tests/r1cs/signature.r1cs");
@@ -363,4 +693,107 @@ mod tests {
         println!("✅ JSON export/import successful!");
         println!("   JSON size: {} bytes", json.len());
     }
+
+    fn push_u32(buf: &mut Vec<u8>, v: u32) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    fn push_u64(buf: &mut Vec<u8>, v: u64) {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    /// Builds a minimal (no constraints) synthetic R1CS file, optionally with
+    /// a single custom gate and one application of it, to exercise the v2
+    /// section parsing without needing a real v2 fixture on disk.
+    fn build_minimal_r1cs(version: u32, include_custom_gates: bool) -> Vec<u8> {
+        let field_size: u32 = 32;
+        let prime = vec![1u8; field_size as usize];
+
+        let mut header = Vec::new();
+        push_u32(&mut header, field_size);
+        header.extend_from_slice(&prime);
+        push_u32(&mut header, 2); // n_wires
+        push_u32(&mut header, 0); // n_pub_out
+        push_u32(&mut header, 1); // n_pub_in
+        push_u32(&mut header, 0); // n_prv_in
+        push_u64(&mut header, 0); // n_labels
+        push_u32(&mut header, 0); // n_constraints
+
+        let mut sections: Vec<(u32, Vec<u8>)> = vec![(0x01, header), (0x02, Vec::new())];
+
+        if include_custom_gates {
+            let mut gates_list = Vec::new();
+            push_u32(&mut gates_list, 1); // n_custom_gates
+            let name = b"MyGate";
+            push_u32(&mut gates_list, name.len() as u32);
+            gates_list.extend_from_slice(name);
+            push_u32(&mut gates_list, 1); // n_params
+            gates_list.extend_from_slice(&vec![7u8; field_size as usize]);
+
+            let mut gates_applied = Vec::new();
+            push_u32(&mut gates_applied, 1); // n_applications
+            push_u32(&mut gates_applied, 0); // custom_gate_id
+            push_u32(&mut gates_applied, 1); // n_signals
+            push_u32(&mut gates_applied, 1); // signal wire id
+
+            sections.push((0x04, gates_list));
+            sections.push((0x05, gates_applied));
+        }
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"r1cs");
+        push_u32(&mut out, version);
+        push_u32(&mut out, sections.len() as u32);
+        for (section_type, bytes) in &sections {
+            push_u32(&mut out, *section_type);
+            push_u64(&mut out, bytes.len() as u64);
+            out.extend_from_slice(bytes);
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_v2_without_custom_gates_matches_v1_behavior() {
+        let bytes = build_minimal_r1cs(2, false);
+        let path = std::env::temp_dir().join("zkenc_test_v2_no_custom_gates.r1cs");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let r1cs =
+            R1csFile::from_file(&path).expect("Failed to parse v2 R1CS without custom gates");
+        assert!(r1cs.custom_gates.is_empty());
+        assert!(r1cs.custom_gate_applications.is_empty());
+        assert_eq!(r1cs.n_wires, 2);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_parse_v2_with_custom_gates() {
+        let bytes = build_minimal_r1cs(2, true);
+        let path = std::env::temp_dir().join("zkenc_test_v2_with_custom_gates.r1cs");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let r1cs = R1csFile::from_file(&path).expect("Failed to parse v2 R1CS with custom gates");
+        assert_eq!(r1cs.custom_gates.len(), 1);
+        assert_eq!(r1cs.custom_gates[0].template_name, "MyGate");
+        assert_eq!(r1cs.custom_gates[0].parameters.len(), 1);
+        assert_eq!(r1cs.custom_gate_applications.len(), 1);
+        assert_eq!(r1cs.custom_gate_applications[0].custom_gate_id, 0);
+        assert_eq!(r1cs.custom_gate_applications[0].signals, vec![1]);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_parse_v1_still_works() {
+        let bytes = build_minimal_r1cs(1, false);
+        let path = std::env::temp_dir().join("zkenc_test_v1_minimal.r1cs");
+        std::fs::write(&path, &bytes).unwrap();
+
+        let r1cs = R1csFile::from_file(&path).expect("Failed to parse v1 R1CS");
+        assert!(r1cs.custom_gates.is_empty());
+        assert!(r1cs.custom_gate_applications.is_empty());
+
+        std::fs::remove_file(&path).ok();
+    }
 }
@@ -31,9 +31,16 @@ pub struct WitnessFile {
 impl WitnessFile {
     /// Load witness from .wtns file
     pub fn from_file(path: &str) -> Result<Self> {
-        let file =
-            File::open(path).with_context(|| format!("Failed to open witness file: {}", path))?;
-        let mut reader = BufReader::new(file);
+        let data =
+            std::fs::read(path).with_context(|| format!("Failed to read witness file: {}", path))?;
+        Self::from_bytes(&data)
+    }
+
+    /// Parse witness from in-memory `.wtns` bytes (same format `from_file`
+    /// reads off disk), for callers that already have the file contents -
+    /// e.g. a WASM host handing over a `Uint8Array`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let mut reader = BufReader::new(data);
 
         // Read magic
         let mut magic = [0u8; 4];
@@ -65,22 +72,51 @@ impl WitnessFile {
                     // Header section
                     let field_size = read_u32_le(&mut reader)?;
 
-                    // Read prime modulus
-                    prime = vec![0u8; field_size as usize];
-                    reader.read_exact(&mut prime)?;
+                    // field_size is an untrusted u32 straight off the file;
+                    // a crafted .wtns can claim e.g. u32::MAX to make an
+                    // upfront vec![0u8; ...] try to allocate gigabytes
+                    // before a single prime byte is read. Grow incrementally
+                    // instead so a truncated/malicious file fails on the
+                    // read_to_end, not on allocation.
+                    prime = Vec::new();
+                    let read = reader
+                        .by_ref()
+                        .take(field_size as u64)
+                        .read_to_end(&mut prime)
+                        .context("Failed to read prime modulus")?;
+                    if read != field_size as usize {
+                        bail!("Unexpected end of data reading prime modulus");
+                    }
 
                     // Read witness count
                     n_witness = read_u32_le(&mut reader)?;
                 }
                 2 => {
                     // Witness data section
-                    witness_data = vec![0u8; section_size as usize];
-                    reader.read_exact(&mut witness_data)?;
+                    // section_size is an untrusted u64 straight off the
+                    // file; same reasoning as the prime modulus above.
+                    witness_data = Vec::new();
+                    let read = reader
+                        .by_ref()
+                        .take(section_size)
+                        .read_to_end(&mut witness_data)
+                        .context("Failed to read witness data")?;
+                    if read != section_size as usize {
+                        bail!("Unexpected end of data reading witness data");
+                    }
                 }
                 _ => {
-                    // Skip unknown sections
-                    let mut skip = vec![0u8; section_size as usize];
-                    reader.read_exact(&mut skip)?;
+                    // Skip unknown sections. section_size is untrusted, so
+                    // don't pre-size the skip buffer either.
+                    let mut skip = Vec::new();
+                    let read = reader
+                        .by_ref()
+                        .take(section_size)
+                        .read_to_end(&mut skip)
+                        .context("Failed to skip unknown section")?;
+                    if read != section_size as usize {
+                        bail!("Unexpected end of data skipping unknown section");
+                    }
                 }
             }
         }
@@ -139,6 +175,14 @@ impl WitnessFile {
     pub fn n_wires(&self) -> u32 {
         self.n_witness
     }
+
+    /// Wire values in wire-index order, as raw little-endian field-element
+    /// bytes straight out of the witness data section.
+    pub fn wire_values(&self) -> Vec<Vec<u8>> {
+        (0..self.n_witness)
+            .map(|wire_id| self.assignments.get(&wire_id).cloned().unwrap_or_default())
+            .collect()
+    }
 }
 
 // Helper functions for reading little-endian integers
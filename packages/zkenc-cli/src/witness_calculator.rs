@@ -0,0 +1,232 @@
+// witness_calculator.rs - Drive a Circom-compiled .wasm witness generator
+//
+// Circom's `--wasm` build target emits a WebAssembly module exposing a
+// standard ABI for loading inputs and reading back the computed witness:
+//   - init(sanityCheck: i32)
+//   - getFieldNumLen32() / getRawPrime()
+//   - readSharedRWMemory(i32) / writeSharedRWMemory(i32, i32)
+//   - setInputSignal(hMSB: i32, hLSB: i32, pos: i32)
+//   - getWitnessSize()
+//   - getWitness(i32)
+// This mirrors that ABI (the same one snarkjs and ark-circom drive) so we
+// can compute a full witness assignment directly from the parsed
+// `parse_inputs` map instead of shelling out to `circom`/`snarkjs`.
+
+use anyhow::{anyhow, Context, Result};
+use ark_bn254::Fr; // Circom uses BN254 (alt_bn128)
+use ark_ff::PrimeField;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use wasmer::{imports, Function, FunctionEnv, FunctionEnvMut, Instance, Module, Store};
+
+/// Size in bytes of one 32-bit word of the wasm module's shared memory. The
+/// number of words per field element is read from the module itself via
+/// `getFieldNumLen32`.
+const SHARED_RW_MEMORY_WORD_SIZE: usize = 4;
+
+/// Wraps a circom `.wasm` witness-generator instance and exposes the
+/// computed witness as a vector of `Fr` ordered by wire index.
+pub struct WitnessCalculator {
+    store: Store,
+    instance: Instance,
+    n32: usize,
+}
+
+impl WitnessCalculator {
+    /// Load and instantiate the witness-generator `.wasm` module.
+    pub fn new<P: AsRef<Path>>(wasm_path: P) -> Result<Self> {
+        let wasm_path = wasm_path.as_ref();
+        let wasm_bytes = std::fs::read(wasm_path)
+            .with_context(|| format!("Failed to read WASM file: {:?}", wasm_path))?;
+
+        let mut store = Store::default();
+        let module = Module::new(&store, &wasm_bytes)
+            .with_context(|| format!("Failed to compile WASM module: {:?}", wasm_path))?;
+
+        let env = FunctionEnv::new(&mut store, ());
+        let import_object = imports! {
+            "runtime" => {
+                "exceptionHandler" => Function::new_typed(&mut store, exception_handler),
+                "showSharedRWMemory" => Function::new_typed_with_env(&mut store, &env, noop_i32),
+                "printErrorMessage" => Function::new_typed(&mut store, noop),
+                "writeBufferMessage" => Function::new_typed(&mut store, noop),
+                "logFinishComponent" => Function::new_typed_with_env(&mut store, &env, noop_i32),
+                "logStartComponent" => Function::new_typed_with_env(&mut store, &env, noop_i32),
+                "logSetSignal" => Function::new_typed(&mut store, noop_i32_i32_i32),
+                "logGetSignal" => Function::new_typed(&mut store, noop_i32_i32_i32),
+            },
+        };
+
+        let instance = Instance::new(&mut store, &module, &import_object)
+            .with_context(|| format!("Failed to instantiate WASM module: {:?}", wasm_path))?;
+
+        let n32 = read_field_num_len32(&mut store, &instance)?;
+
+        Ok(Self {
+            store,
+            instance,
+            n32,
+        })
+    }
+
+    /// Assign the parsed circuit inputs and compute the full witness.
+    ///
+    /// `inputs` preserves the array ordering produced by `circom::parse_inputs`
+    /// (flattened arrays are fed in-order starting at index 0 of each signal).
+    pub fn calculate_witness(
+        &mut self,
+        inputs: &HashMap<String, Vec<String>>,
+        sanity_check: bool,
+    ) -> Result<Vec<Fr>> {
+        let init = self.exported_fn("init")?;
+        init.call(&mut self.store, &[wasmer::Value::I32(sanity_check as i32)])
+            .context("Failed to call init() in witness WASM module")?;
+
+        let set_input_signal = self.exported_fn("setInputSignal")?;
+        for (name, values) in inputs {
+            let (h_msb, h_lsb) = signal_name_hash(name);
+            for (pos, value) in values.iter().enumerate() {
+                let field_value =
+                    Fr::from_str(value).map_err(|_| anyhow!("Invalid field element: {}", value))?;
+                self.write_shared_rw_memory(field_value)?;
+                set_input_signal
+                    .call(
+                        &mut self.store,
+                        &[
+                            wasmer::Value::I32(h_msb),
+                            wasmer::Value::I32(h_lsb),
+                            wasmer::Value::I32(pos as i32),
+                        ],
+                    )
+                    .with_context(|| format!("Failed to set input signal '{}'", name))?;
+            }
+        }
+
+        self.read_witness()
+    }
+
+    fn write_shared_rw_memory(&mut self, value: Fr) -> Result<()> {
+        let write_shared = self.exported_fn("writeSharedRWMemory")?;
+        let bytes = value.into_bigint().to_bytes_le();
+        for word_idx in 0..self.n32 {
+            let start = word_idx * SHARED_RW_MEMORY_WORD_SIZE;
+            let mut word_bytes = [0u8; SHARED_RW_MEMORY_WORD_SIZE];
+            if start < bytes.len() {
+                let end = (start + SHARED_RW_MEMORY_WORD_SIZE).min(bytes.len());
+                word_bytes[..end - start].copy_from_slice(&bytes[start..end]);
+            }
+            let word = u32::from_le_bytes(word_bytes);
+            write_shared
+                .call(
+                    &mut self.store,
+                    &[
+                        wasmer::Value::I32(word_idx as i32),
+                        wasmer::Value::I32(word as i32),
+                    ],
+                )
+                .context("Failed to write shared RW memory")?;
+        }
+        Ok(())
+    }
+
+    fn read_witness(&mut self) -> Result<Vec<Fr>> {
+        let get_witness_size = self.exported_fn("getWitnessSize")?;
+        let witness_size = get_witness_size
+            .call(&mut self.store, &[])
+            .context("Failed to call getWitnessSize()")?[0]
+            .i32()
+            .ok_or_else(|| anyhow!("getWitnessSize() did not return an i32"))?
+            as usize;
+
+        let get_witness = self.exported_fn("getWitness")?;
+        let read_shared = self.exported_fn("readSharedRWMemory")?;
+
+        let mut witness = Vec::with_capacity(witness_size);
+        for i in 0..witness_size {
+            get_witness
+                .call(&mut self.store, &[wasmer::Value::I32(i as i32)])
+                .with_context(|| format!("Failed to call getWitness({})", i))?;
+
+            let mut bytes = vec![0u8; self.n32 * SHARED_RW_MEMORY_WORD_SIZE];
+            for word_idx in 0..self.n32 {
+                let word = read_shared
+                    .call(&mut self.store, &[wasmer::Value::I32(word_idx as i32)])
+                    .context("Failed to read shared RW memory")?[0]
+                    .i32()
+                    .ok_or_else(|| anyhow!("readSharedRWMemory() did not return an i32"))?;
+                let start = word_idx * SHARED_RW_MEMORY_WORD_SIZE;
+                bytes[start..start + SHARED_RW_MEMORY_WORD_SIZE]
+                    .copy_from_slice(&(word as u32).to_le_bytes());
+            }
+
+            witness.push(Fr::from_le_bytes_mod_order(&bytes));
+        }
+
+        Ok(witness)
+    }
+
+    fn exported_fn(&self, name: &str) -> Result<wasmer::Function> {
+        self.instance
+            .exports
+            .get_function(name)
+            .cloned()
+            .with_context(|| format!("Witness WASM module is missing export '{}'", name))
+    }
+}
+
+fn read_field_num_len32(store: &mut Store, instance: &Instance) -> Result<usize> {
+    let get_field_num_len32 = instance
+        .exports
+        .get_function("getFieldNumLen32")
+        .context("Witness WASM module is missing export 'getFieldNumLen32'")?;
+    let n32 = get_field_num_len32
+        .call(store, &[])
+        .context("Failed to call getFieldNumLen32()")?[0]
+        .i32()
+        .ok_or_else(|| anyhow!("getFieldNumLen32() did not return an i32"))?;
+    Ok(n32 as usize)
+}
+
+/// Circom addresses signals by a 64-bit hash of their name, split across two
+/// i32 arguments. This matches the hashing scheme `setInputSignal` expects.
+fn signal_name_hash(name: &str) -> (i32, i32) {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for &byte in name.as_bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    ((hash >> 32) as i32, hash as i32)
+}
+
+fn exception_handler(_code: i32) {}
+fn noop() {}
+fn noop_i32(_env: FunctionEnvMut<()>, _a: i32) {}
+fn noop_i32_i32_i32(_a: i32, _b: i32, _c: i32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore] // Only run when a compiled circuit.wasm fixture is available
+    fn test_calculate_sudoku_witness() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/r1cs/sudoku_basic.wasm"
+        );
+
+        let mut calculator =
+            WitnessCalculator::new(path).expect("Failed to load witness calculator");
+
+        let mut inputs = HashMap::new();
+        inputs.insert("puzzle".to_string(), vec!["1".to_string(); 81]);
+        inputs.insert("solution".to_string(), vec!["1".to_string(); 81]);
+
+        let witness = calculator
+            .calculate_witness(&inputs, true)
+            .expect("Failed to calculate witness");
+
+        assert_eq!(witness[0], Fr::from(1u64));
+    }
+}
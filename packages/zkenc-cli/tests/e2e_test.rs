@@ -52,6 +52,9 @@ fn test_sudoku_e2e() -> Result<()> {
         input_path.to_str().unwrap(),
         ciphertext_path.to_str().unwrap(),
         key1_path.to_str().unwrap(),
+        None,
+        false,
+        zkenc_cli::formats::OutputFormat::Binary,
     )?;
 
     // Verify files were created
@@ -74,6 +77,10 @@ fn test_sudoku_e2e() -> Result<()> {
         message_path.to_str().unwrap(),
         combined_ciphertext_path.to_str().unwrap(),
         true,
+        false,
+        false,
+        false,
+        zkenc_cli::formats::OutputFormat::Binary,
     )?;
 
     assert!(combined_ciphertext_path.exists(), "Combined ciphertext file should exist");
@@ -88,6 +95,8 @@ fn test_sudoku_e2e() -> Result<()> {
         witness_path.to_str().unwrap(),
         ciphertext_path.to_str().unwrap(),
         key2_path.to_str().unwrap(),
+        None,
+        zkenc_cli::formats::OutputFormat::Binary,
     )?;
 
     assert!(key2_path.exists(), "Recovered key file should exist");
@@ -101,6 +110,9 @@ fn test_sudoku_e2e() -> Result<()> {
         witness_path.to_str().unwrap(),
         combined_ciphertext_path.to_str().unwrap(),
         decrypted_path.to_str().unwrap(),
+        false,
+        false,
+        zkenc_cli::formats::OutputFormat::Binary,
     )?;
 
     assert!(decrypted_path.exists(), "Decrypted file should exist");
@@ -170,6 +182,9 @@ fn test_sudoku_e2e_wrong_witness() -> Result<()> {
         input_path.to_str().unwrap(),
         ciphertext_path.to_str().unwrap(),
         key_path.to_str().unwrap(),
+        None,
+        false,
+        zkenc_cli::formats::OutputFormat::Binary,
     )?;
 
     // Try to decap with wrong witness (should fail)
@@ -179,6 +194,8 @@ fn test_sudoku_e2e_wrong_witness() -> Result<()> {
         wrong_witness_path.to_str().unwrap(),
         ciphertext_path.to_str().unwrap(),
         temp_dir.join("wrong_key.bin").to_str().unwrap(),
+        None,
+        zkenc_cli::formats::OutputFormat::Binary,
     );
 
     match result {
@@ -2,16 +2,73 @@
 //!
 //! Implementation of Encap, Decap, and Verify algorithms.
 
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::{Pairing, PairingOutput};
 use ark_ec::{CurveGroup, PrimeGroup, VariableBaseMSM};
-use ark_ff::{Field, One, UniformRand};
+use ark_ff::{Field, One, PrimeField, UniformRand};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_relations::gr1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_serialize::CanonicalSerialize;
 use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
+use hkdf::Hkdf;
+use sha2::Sha256;
 
 use crate::data_structures::{Ciphertext, EncapKey, Key};
 use crate::r1cs_to_qap;
 
+/// Strategy for turning a WKEM pairing target into a 32-byte symmetric [`Key`].
+///
+/// `encap` and `decap` both reduce to a shared `GT` pairing target; this
+/// trait is the last step that hashes it down to a key. The default
+/// implementation ([`DefaultKdf`]) expands the target through HKDF-SHA256
+/// with an `info` string that also binds the curve's scalar-field modulus
+/// and the circuit's public inputs, so two different circuits/instances can
+/// never collide on a key even if their raw pairing targets did. Implement
+/// this trait for your own zero-sized type to plug in a different KDF or
+/// domain-separation label.
+pub trait KeyDerivation<E: Pairing> {
+    /// Domain-separation label mixed into the KDF's `info` parameter.
+    /// Override this to scope keys from a fork or application into their
+    /// own namespace without having to reimplement [`derive_key`](Self::derive_key).
+    fn label(&self) -> &[u8] {
+        b"zkenc-wkem-key-v1"
+    }
+
+    /// Derive the symmetric key from the raw pairing target `s` and the
+    /// public inputs (constant `1` excluded) that produced it.
+    fn derive_key(
+        &self,
+        pairing_target: &PairingOutput<E>,
+        public_inputs: &[E::ScalarField],
+    ) -> Result<Key, Error> {
+        let mut ikm = Vec::new();
+        pairing_target
+            .serialize_compressed(&mut ikm)
+            .map_err(|_| Error::SerializationError)?;
+
+        let mut info = Vec::new();
+        info.extend_from_slice(self.label());
+        info.extend_from_slice(&E::ScalarField::MODULUS.to_bytes_le());
+        for input in public_inputs {
+            input
+                .serialize_compressed(&mut info)
+                .map_err(|_| Error::SerializationError)?;
+        }
+
+        let hk = Hkdf::<Sha256>::new(None, &ikm);
+        let mut key_bytes = [0u8; 32];
+        hk.expand(&info, &mut key_bytes)
+            .map_err(|_| Error::SerializationError)?;
+
+        Ok(Key::new(key_bytes))
+    }
+}
+
+/// The default [`KeyDerivation`]: HKDF-SHA256 with the `"zkenc-wkem-key-v1"` label.
+pub struct DefaultKdf;
+
+impl<E: Pairing> KeyDerivation<E> for DefaultKdf {}
+
 /// Error types for WKEM operations
 #[derive(Debug)]
 pub enum Error {
@@ -49,6 +106,22 @@ where
     E: Pairing,
     C: ConstraintSynthesizer<E::ScalarField>,
     R: RngCore,
+{
+    encap_with_kdf(circuit, rng, &DefaultKdf)
+}
+
+/// Same as [`encap`], but lets the caller supply a [`KeyDerivation`] strategy
+/// instead of the default HKDF-SHA256 one.
+pub fn encap_with_kdf<E, C, R, K>(
+    circuit: C,
+    rng: &mut R,
+    kdf: &K,
+) -> Result<(Ciphertext<E>, Key), Error>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField>,
+    R: RngCore,
+    K: KeyDerivation<E>,
 {
     #[cfg(feature = "std")]
     println!("🔐 Starting Encap...");
@@ -78,8 +151,8 @@ where
     // Even if witness assignment failed, public inputs should be available
     let cs_borrowed = cs.borrow().unwrap();
     let instance_result = cs_borrowed.instance_assignment();
-    let public_inputs: Vec<E::ScalarField> = match instance_result {
-        Ok(assignment) => assignment[1..].to_vec(), // Skip the constant 1 at index 0
+    let instance_assignment: Vec<E::ScalarField> = match instance_result {
+        Ok(assignment) => assignment,
         Err(_) => {
             // If we can't get assignments, try to get number of public inputs at least
             #[cfg(feature = "std")]
@@ -88,6 +161,11 @@ where
         }
     };
     drop(cs_borrowed); // Release the borrow
+    // Skip the constant 1 at index 0
+    let public_inputs: Vec<E::ScalarField> = instance_assignment
+        .get(1..)
+        .map(|s| s.to_vec())
+        .unwrap_or_default();
 
     #[cfg(feature = "std")]
     println!("  ✓ Extracted {} public inputs", public_inputs.len());
@@ -110,7 +188,8 @@ where
     println!("  ⏳ Evaluating QAP polynomials at x...");
 
     // Evaluate u_i(x), v_i(x), w_i(x) for all variables
-    let (u_evals, v_evals, w_evals) = r1cs_to_qap::evaluate_qap_polynomials_at_x(&cs, x);
+    let (u_evals, v_evals, w_evals) = r1cs_to_qap::evaluate_qap_polynomials_at_x(&cs, x)
+        .map_err(|e| Error::SynthesisError(format!("{}", e)))?;
 
     // Compute query vectors:
     // r_u_query_g1[i] = [r·u_i(x)]₁
@@ -132,10 +211,29 @@ where
         phi_delta_query_g1.push((g1_generator * phi_i_delta).into_affine());
     }
 
-    // h_query_g1: Placeholder for quotient polynomial evaluation
-    // In full implementation: h(x) = (A(x)·B(x) - C(x)) / t(x) where t(x) is vanishing polynomial
-    // For now, use empty vector as this is only needed for verification
-    let h_query_g1 = Vec::new();
+    // h_query_g1[i] = [xⁱ·t(x)/δ]₁ for i = 0..n-2, the basis a prover later
+    // commits the quotient polynomial h(x) = (A(x)·B(x) - C(x))/t(x)
+    // against. t(X) = X^n - 1 is the QAP evaluation domain's vanishing
+    // polynomial (the same domain `evaluate_qap_polynomials_at_x` used
+    // above), so t(x) is just that domain's vanishing polynomial evaluated
+    // at the already-sampled x - no FFT needed here since x is a single
+    // point, not the whole domain.
+    let domain = GeneralEvaluationDomain::<E::ScalarField>::new(r1cs_to_qap::qap_domain_size(&cs));
+    let h_query_g1 = match domain {
+        Some(domain) => {
+            let t_at_x = domain.evaluate_vanishing_polynomial(x);
+            let delta_inv = delta.inverse().expect("delta must be non-zero");
+            let mut h_query_g1 = Vec::with_capacity(domain.size().saturating_sub(1));
+            let mut x_pow = E::ScalarField::one();
+            for _ in 0..domain.size().saturating_sub(1) {
+                let h_i = x_pow * t_at_x * delta_inv;
+                h_query_g1.push((g1_generator * h_i).into_affine());
+                x_pow *= x;
+            }
+            h_query_g1
+        }
+        None => Vec::new(),
+    };
 
     #[cfg(feature = "std")]
     println!("  ✓ Generated CRS with {} query elements", m);
@@ -155,66 +253,56 @@ where
         public_inputs: public_inputs.clone(),
     };
 
-    // Step 5: Compute pairing s = [α]₁ · [β]₂ (simplified for encap without witness)
+    // Step 5: Derive the key from the public-input-only pairing target.
+    //
+    // encap only has public inputs (no witness), so it can only derive the
+    // "public part" of the pairing target s = e(α,β) · e(Σᵢ aᵢ·φᵢ(x), g2)
+    // for i ≤ l. `decap` reconstructs this exact same s from a full
+    // witness-assigned circuit (via the A/B/C relation) using this same
+    // helper, so the two sides can never diverge.
     #[cfg(feature = "std")]
     println!("  ⏳ Computing pairing for key derivation...");
 
-    // In encap, we only have public inputs, not full witness
-    // For a full WKEM implementation with QAP evaluation, we would compute:
-    // s = [α]₁ · [β]₂ + Σᵢ aᵢ · [φᵢ(x)]₁ · [1]₂
-    // But since we don't have witness values, use simplified pairing with only public inputs
+    let s = public_input_pairing_target::<E>(&ciphertext.encap_key, &instance_assignment)?;
+    let key = kdf.derive_key(&s, &public_inputs)?;
 
-    // Compute Σᵢ aᵢ · [φᵢ(x)]₁ for public inputs only (indices 0 to l)
-    let mut phi_sum_affine = Vec::new();
-    let mut scalars = Vec::new();
+    #[cfg(feature = "std")]
+    println!("  ✓ Derived key from pairing");
 
-    // Add constant 1 at index 0
-    if !ciphertext.encap_key.phi_delta_query_g1.is_empty() {
-        phi_sum_affine.push(ciphertext.encap_key.phi_delta_query_g1[0]);
-        scalars.push(E::ScalarField::one());
-    }
+    Ok((ciphertext, key))
+}
 
-    // Add public inputs
-    for (idx, &a_i) in public_inputs.iter().enumerate() {
-        let i = idx + 1; // Skip constant 1 at index 0
-        if i < ciphertext.encap_key.phi_delta_query_g1.len() {
-            phi_sum_affine.push(ciphertext.encap_key.phi_delta_query_g1[i]);
-            scalars.push(a_i);
-        }
-    }
+/// Compute the public-input-only pairing target
+/// `s = e(α,β) · e(Σᵢ₌₀^l aᵢ·φᵢ(x), g2)`, where `instance_assignment` is
+/// `[1, public_input_1, ..., public_input_l]` (the constant 1 at index 0,
+/// matching `phi_delta_query_g1`'s indexing).
+///
+/// Both `encap` (which only has public inputs) and `decap` (which derives
+/// the same target via the full A/B/C relation) funnel through this single
+/// function so their key derivation can never drift apart.
+fn public_input_pairing_target<E: Pairing>(
+    encap_key: &EncapKey<E>,
+    instance_assignment: &[E::ScalarField],
+) -> Result<PairingOutput<E>, Error> {
+    let g1_generator = E::G1::generator();
+    let g2_generator = E::G2::generator();
+
+    // Compute Σᵢ aᵢ · [φᵢ(x)/δ]₁ for i = 0..=l (constant 1 plus public inputs)
+    let len = core::cmp::min(instance_assignment.len(), encap_key.phi_delta_query_g1.len());
+    let phi_sum_affine = &encap_key.phi_delta_query_g1[..len];
+    let scalars = &instance_assignment[..len];
 
-    // MSM: compute Σᵢ aᵢ · Pᵢ for public inputs
     let phi_sum = if !phi_sum_affine.is_empty() {
-        E::G1::msm(&phi_sum_affine, &scalars).map_err(|_| Error::SerializationError)?
+        E::G1::msm(phi_sum_affine, scalars).map_err(|_| Error::SerializationError)?
     } else {
         // If no public inputs, use zero (identity in additive group)
         g1_generator - g1_generator
     };
 
-    // Compute s = [α]₁ · [β]₂ + (Σᵢ aᵢ · [φᵢ(x)]₁) · [1]₂
-    let pairing1 = E::pairing(ciphertext.encap_key.alpha_g1, ciphertext.encap_key.beta_g2);
+    // s = [α]₁ · [β]₂ + (Σᵢ aᵢ · [φᵢ(x)]₁) · [1]₂
+    let pairing1 = E::pairing(encap_key.alpha_g1, encap_key.beta_g2);
     let pairing2 = E::pairing(phi_sum, g2_generator);
-    let s = pairing1 + pairing2;
-
-    // Serialize pairing result and hash to get key
-    use ark_serialize::CanonicalSerialize;
-    let mut s_bytes = Vec::new();
-    s.serialize_compressed(&mut s_bytes)
-        .map_err(|_| Error::SerializationError)?;
-
-    // Derive the key from pairing result
-    // In production, should use proper KDF like HKDF or Blake3
-    // For now, use first 32 bytes of serialized pairing result
-    let mut key_bytes = [0u8; 32];
-    let len = core::cmp::min(32, s_bytes.len());
-    key_bytes[..len].copy_from_slice(&s_bytes[..len]);
-
-    let key = Key::new(key_bytes);
-
-    #[cfg(feature = "std")]
-    println!("  ✓ Derived key from pairing");
-
-    Ok((ciphertext, key))
+    Ok(pairing1 + pairing2)
 }
 
 /// Decapsulate: Recover key using witness
@@ -229,6 +317,24 @@ pub fn decap<E, C>(circuit: C, ciphertext: &Ciphertext<E>) -> Result<Key, Error>
 where
     E: Pairing,
     C: ConstraintSynthesizer<E::ScalarField>,
+{
+    decap_with_kdf(circuit, ciphertext, &DefaultKdf)
+}
+
+/// Same as [`decap`], but lets the caller supply a [`KeyDerivation`] strategy
+/// instead of the default HKDF-SHA256 one. Must match the strategy
+/// [`encap_with_kdf`] used to produce `ciphertext`; with a different
+/// strategy the returned key differs from the one `encap` produced, and
+/// this mismatch is not detected here.
+pub fn decap_with_kdf<E, C, K>(
+    circuit: C,
+    ciphertext: &Ciphertext<E>,
+    kdf: &K,
+) -> Result<Key, Error>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField>,
+    K: KeyDerivation<E>,
 {
     #[cfg(feature = "std")]
     println!("🔓 Starting Decap...");
@@ -249,9 +355,95 @@ where
     #[cfg(feature = "std")]
     println!("  ✓ Circuit synthesized and satisfied");
 
-    // TODO: Implement key recovery
-    let _ = ciphertext;
-    todo!("Decap implementation in progress")
+    // Step 2: Pull the full variable assignment (constant + public inputs,
+    // then witness) out of the satisfied constraint system.
+    let cs_borrowed = cs.borrow().unwrap();
+    let instance_assignment = cs_borrowed
+        .instance_assignment()
+        .map_err(|_| Error::InvalidPublicInputs)?;
+    let witness_assignment = cs_borrowed
+        .witness_assignment()
+        .map_err(|_| Error::InvalidWitness)?;
+    drop(cs_borrowed);
+
+    let mut full_assignment = instance_assignment.clone();
+    full_assignment.extend_from_slice(&witness_assignment);
+
+    let encap_key = &ciphertext.encap_key;
+    let g1_generator = E::G1::generator();
+
+    // Step 3: Rebuild A = [α]₁ + Σᵢ aᵢ·[r·uᵢ(x)]₁ and
+    // B = [β]₂ + Σᵢ aᵢ·[r·vᵢ(x)]₂ over the FULL assignment (public + witness).
+    let msm_len = full_assignment
+        .len()
+        .min(encap_key.r_u_query_g1.len())
+        .min(encap_key.r_v_query_g2.len());
+
+    let a_sum = E::G1::msm(&encap_key.r_u_query_g1[..msm_len], &full_assignment[..msm_len])
+        .map_err(|_| Error::SerializationError)?;
+    let a_commit = encap_key.alpha_g1.into_group() + a_sum;
+
+    let b_sum = E::G2::msm(&encap_key.r_v_query_g2[..msm_len], &full_assignment[..msm_len])
+        .map_err(|_| Error::SerializationError)?;
+    let b_commit = encap_key.beta_g2.into_group() + b_sum;
+
+    // Step 4: Rebuild C = Σ_{i>l} aᵢ·[φᵢ(x)/δ]₁ + Σⱼ hⱼ·[xʲt(x)/δ]₁, the
+    // private (witness-only) part of the query vectors plus the quotient
+    // polynomial's commitment.
+    let witness_start = instance_assignment.len();
+    let phi_witness_sum = if witness_start < encap_key.phi_delta_query_g1.len()
+        && witness_start < full_assignment.len()
+    {
+        let phi_end = full_assignment
+            .len()
+            .min(encap_key.phi_delta_query_g1.len());
+        E::G1::msm(
+            &encap_key.phi_delta_query_g1[witness_start..phi_end],
+            &full_assignment[witness_start..phi_end],
+        )
+        .map_err(|_| Error::SerializationError)?
+    } else {
+        g1_generator - g1_generator
+    };
+
+    let h_coefficients = r1cs_to_qap::compute_h_coefficients(&cs, &full_assignment);
+    let h_len = h_coefficients.len().min(encap_key.h_query_g1.len());
+    let h_sum = if h_len > 0 {
+        E::G1::msm(&encap_key.h_query_g1[..h_len], &h_coefficients[..h_len])
+            .map_err(|_| Error::SerializationError)?
+    } else {
+        g1_generator - g1_generator
+    };
+
+    let c_commit = phi_witness_sum + h_sum;
+
+    // Step 5: e(A,B) = e(α,β) · e(Σ_{i≤l} aᵢφᵢ(x), g2) · e(C,δ₂) is an
+    // identity of the QAP relation once h is the true quotient, so the
+    // public-only part of it (computed the exact same way `encap` did)
+    // must equal e(A,B) - e(C,δ₂). If it doesn't, either the witness isn't
+    // the one the ciphertext was encapsulated for, or h/the query vectors
+    // are inconsistent - either way the key can't be trusted.
+    let ab = E::pairing(a_commit, b_commit);
+    let c_pairing = E::pairing(c_commit, encap_key.delta_g2);
+    let reconstructed_public_target = ab - c_pairing;
+
+    // instance_assignment[1..] excludes the constant 1, matching the
+    // `public_inputs` slice `encap_with_kdf` bound the key to.
+    let public_inputs = instance_assignment.get(1..).unwrap_or(&[]);
+    let expected_target = public_input_pairing_target::<E>(encap_key, &instance_assignment)?;
+    let expected_key = kdf.derive_key(&expected_target, public_inputs)?;
+    let reconstructed_key = kdf.derive_key(&reconstructed_public_target, public_inputs)?;
+
+    if reconstructed_key != expected_key {
+        #[cfg(feature = "std")]
+        eprintln!("  ✗ Recomputed pairing target does not match ciphertext's key");
+        return Err(Error::InvalidWitness);
+    }
+
+    #[cfg(feature = "std")]
+    println!("  ✓ Recovered key from witness");
+
+    Ok(expected_key)
 }
 
 /// Verify that a ciphertext is well-formed
@@ -298,10 +490,30 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ark_bn254::Bn254;
 
     #[test]
     fn test_error_display() {
         let err = Error::InvalidWitness;
         assert!(format!("{}", err).contains("Invalid witness"));
     }
+
+    struct NamespacedKdf;
+
+    impl KeyDerivation<Bn254> for NamespacedKdf {
+        fn label(&self) -> &[u8] {
+            b"my-app-v1"
+        }
+    }
+
+    #[test]
+    fn test_custom_kdf_label_changes_derived_key() {
+        let target = PairingOutput::<Bn254>::default();
+        let inputs: [<Bn254 as Pairing>::ScalarField; 0] = [];
+
+        let default_key = DefaultKdf.derive_key(&target, &inputs).unwrap();
+        let namespaced_key = NamespacedKdf.derive_key(&target, &inputs).unwrap();
+
+        assert_ne!(default_key, namespaced_key);
+    }
 }
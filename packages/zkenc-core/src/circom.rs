@@ -0,0 +1,663 @@
+//! Parsing for circom's binary `.r1cs`/`.wtns` artifacts and the generic
+//! `ConstraintSynthesizer` that bridges a parsed R1CS into `encap`/`decap`.
+//!
+//! This lives in zkenc-core so zkenc-js's WASM bindings don't have to
+//! duplicate the parser/synthesizer themselves. zkenc-cli does not use this
+//! module - it maintains its own parser (`r1cs.rs`/`witness.rs`/`circuit.rs`)
+//! that has since grown support this one doesn't have, namely R1CS v2/
+//! custom-gates sections and `.sym`-named witness assembly, and
+//! [`parse_r1cs`] hard-rejects anything but version 1.
+
+use ark_ff::PrimeField;
+use ark_relations::gr1cs::{
+    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
+    R1CS_PREDICATE_LABEL,
+};
+use std::collections::HashMap;
+
+/// Header fields read from an R1CS file's type-1 section, plus the
+/// wire/label mapping from its (optional) type-3 section.
+pub struct R1csHeader {
+    pub field_size: u32,
+    pub prime: Vec<u8>,
+    pub n_wires: u32,
+    pub n_pub_out: u32,
+    pub n_pub_in: u32,
+    pub n_constraints: u32,
+    /// wire index -> label id, from the type-3 section (falls back to identity if absent)
+    pub wire_to_label: Vec<u64>,
+}
+
+impl R1csHeader {
+    pub fn n_public_inputs(&self) -> u32 {
+        self.n_pub_out + self.n_pub_in
+    }
+
+    /// Inverse of `wire_to_label`: label id -> wire index.
+    ///
+    /// Used to place witness entries (which a `.wtns` file orders by label id)
+    /// onto the wire index the constraint system actually expects, instead of
+    /// assuming the two orderings coincide.
+    pub fn label_to_wire(&self) -> HashMap<u64, u32> {
+        self.wire_to_label
+            .iter()
+            .enumerate()
+            .map(|(wire_id, &label)| (label, wire_id as u32))
+            .collect()
+    }
+}
+
+pub struct R1csConstraint {
+    pub a_factors: Vec<(u32, Vec<u8>)>,
+    pub b_factors: Vec<(u32, Vec<u8>)>,
+    pub c_factors: Vec<(u32, Vec<u8>)>,
+}
+
+/// Parse a binary `.r1cs` file (format version 1) into its header and
+/// constraint list.
+pub fn parse_r1cs(data: &[u8]) -> Result<(R1csHeader, Vec<R1csConstraint>), String> {
+    let mut pos = 0;
+
+    let read_u32 = |pos: &mut usize| -> Result<u32, String> {
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+        *pos += 4;
+        Ok(val)
+    };
+
+    let read_u64 = |pos: &mut usize| -> Result<u64, String> {
+        if *pos + 8 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u64::from_le_bytes([
+            data[*pos],
+            data[*pos + 1],
+            data[*pos + 2],
+            data[*pos + 3],
+            data[*pos + 4],
+            data[*pos + 5],
+            data[*pos + 6],
+            data[*pos + 7],
+        ]);
+        *pos += 8;
+        Ok(val)
+    };
+
+    // Check magic "r1cs"
+    if pos + 4 > data.len() || &data[pos..pos + 4] != b"r1cs" {
+        return Err("Invalid R1CS file: wrong magic".to_string());
+    }
+    pos += 4;
+
+    // Version must be 1
+    let version = read_u32(&mut pos)?;
+    if version != 1 {
+        return Err(format!("Unsupported R1CS version: {}", version));
+    }
+
+    // Number of sections
+    let n_sections = read_u32(&mut pos)?;
+
+    // First pass: collect all section positions
+    let mut sections = Vec::new();
+    for _ in 0..n_sections {
+        let section_type = read_u32(&mut pos)?;
+        let section_len = read_u64(&mut pos)? as usize;
+        let section_start = pos;
+        sections.push((section_type, section_len, section_start));
+        pos = section_start + section_len;
+    }
+
+    // Second pass: find and parse header section first
+    let mut header = {
+        let header_section = sections
+            .iter()
+            .find(|(t, _, _)| *t == 0x01)
+            .ok_or("Header section (type 1) not found")?;
+
+        let mut header_pos = header_section.2;
+        let field_size = read_u32(&mut header_pos)?;
+        let prime_len = field_size as usize;
+        if header_pos + prime_len > data.len() {
+            return Err("Invalid prime length".to_string());
+        }
+        let prime = data[header_pos..header_pos + prime_len].to_vec();
+        header_pos += prime_len;
+
+        // Curve identification (and rejection of unrecognized primes) is left
+        // to the caller, which dispatches to the matching curve's
+        // `encap`/`decap` instantiation instead of assuming a fixed curve.
+
+        let n_wires = read_u32(&mut header_pos)?;
+        let n_pub_out = read_u32(&mut header_pos)?;
+        let n_pub_in = read_u32(&mut header_pos)?;
+        let _n_prv_in = read_u32(&mut header_pos)?;
+        let _n_labels = read_u64(&mut header_pos)?;
+        let n_constraints = read_u32(&mut header_pos)?;
+
+        R1csHeader {
+            field_size,
+            prime,
+            n_wires,
+            n_pub_out,
+            n_pub_in,
+            n_constraints,
+            wire_to_label: Vec::new(), // filled in once the section table is known
+        }
+    };
+
+    // Third pass: parse constraints section
+    let constraints = {
+        let constraints_section = sections
+            .iter()
+            .find(|(t, _, _)| *t == 0x02)
+            .ok_or("Constraints section (type 2) not found")?;
+
+        let mut constraints_pos = constraints_section.2;
+        let mut constraints = Vec::new();
+
+        for _ in 0..header.n_constraints {
+            let a_factors =
+                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
+            let b_factors =
+                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
+            let c_factors =
+                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
+
+            constraints.push(R1csConstraint {
+                a_factors,
+                b_factors,
+                c_factors,
+            });
+        }
+
+        constraints
+    };
+
+    // Fourth pass: parse the wire-to-label-id map (type 3), falling back to the
+    // identity mapping (wire index == label id) when the section is absent.
+    header.wire_to_label = match sections.iter().find(|(t, _, _)| *t == 0x03) {
+        Some(&(_, section_len, section_start)) => {
+            let n_entries = section_len / 8;
+            let mut pos = section_start;
+            // n_entries is derived from the untrusted section_len, which
+            // isn't itself validated against data.len() by the first pass
+            // above; don't pre-reserve capacity for it (same reasoning as
+            // decode_parsed_circuit's wire_to_label).
+            let mut labels = Vec::new();
+            for _ in 0..n_entries {
+                labels.push(read_u64(&mut pos)?);
+            }
+            labels
+        }
+        None => (0..header.n_wires as u64).collect(),
+    };
+
+    Ok((header, constraints))
+}
+
+fn parse_linear_combination(
+    data: &[u8],
+    pos: &mut usize,
+    field_size: u32,
+) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    if *pos + 4 > data.len() {
+        return Err("Unexpected end of data in LC".to_string());
+    }
+    let n_factors =
+        u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+    *pos += 4;
+
+    let mut factors = Vec::new();
+    for _ in 0..n_factors {
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data reading wire id".to_string());
+        }
+        let wire_id =
+            u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+        *pos += 4;
+
+        let value_len = field_size as usize;
+        if *pos + value_len > data.len() {
+            return Err("Unexpected end of data reading factor value".to_string());
+        }
+        let value = data[*pos..*pos + value_len].to_vec();
+        *pos += value_len;
+
+        factors.push((wire_id, value));
+    }
+
+    Ok(factors)
+}
+
+/// Serialize a parsed `(R1csHeader, Vec<R1csConstraint>)` to a compact
+/// binary form.
+///
+/// `parse_r1cs` only depends on the circuit, not on any witness or
+/// instance, so a caller that calls `encap`/`decap` many times against the
+/// same circuit (e.g. a browser WASM host) can parse the `.r1cs` bytes once,
+/// cache the result of this function instead (in IndexedDB, say), and skip
+/// re-running `parse_r1cs` on every call. The format mirrors `.r1cs` itself:
+/// little-endian integers and length-prefixed vectors, with no section
+/// table since this is always read back by the same code that wrote it.
+pub fn encode_parsed_circuit(header: &R1csHeader, constraints: &[R1csConstraint]) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    out.extend_from_slice(&header.field_size.to_le_bytes());
+    out.extend_from_slice(&(header.prime.len() as u32).to_le_bytes());
+    out.extend_from_slice(&header.prime);
+    out.extend_from_slice(&header.n_wires.to_le_bytes());
+    out.extend_from_slice(&header.n_pub_out.to_le_bytes());
+    out.extend_from_slice(&header.n_pub_in.to_le_bytes());
+    out.extend_from_slice(&header.n_constraints.to_le_bytes());
+
+    out.extend_from_slice(&(header.wire_to_label.len() as u32).to_le_bytes());
+    for label in &header.wire_to_label {
+        out.extend_from_slice(&label.to_le_bytes());
+    }
+
+    out.extend_from_slice(&(constraints.len() as u32).to_le_bytes());
+    for constraint in constraints {
+        encode_linear_combination(&mut out, &constraint.a_factors);
+        encode_linear_combination(&mut out, &constraint.b_factors);
+        encode_linear_combination(&mut out, &constraint.c_factors);
+    }
+
+    out
+}
+
+fn encode_linear_combination(out: &mut Vec<u8>, factors: &[(u32, Vec<u8>)]) {
+    out.extend_from_slice(&(factors.len() as u32).to_le_bytes());
+    for (wire_id, value) in factors {
+        out.extend_from_slice(&wire_id.to_le_bytes());
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(value);
+    }
+}
+
+/// Inverse of [`encode_parsed_circuit`].
+pub fn decode_parsed_circuit(data: &[u8]) -> Result<(R1csHeader, Vec<R1csConstraint>), String> {
+    let mut pos = 0;
+
+    let read_u32 = |pos: &mut usize| -> Result<u32, String> {
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+        *pos += 4;
+        Ok(val)
+    };
+
+    let read_u64 = |pos: &mut usize| -> Result<u64, String> {
+        if *pos + 8 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u64::from_le_bytes([
+            data[*pos],
+            data[*pos + 1],
+            data[*pos + 2],
+            data[*pos + 3],
+            data[*pos + 4],
+            data[*pos + 5],
+            data[*pos + 6],
+            data[*pos + 7],
+        ]);
+        *pos += 8;
+        Ok(val)
+    };
+
+    let field_size = read_u32(&mut pos)?;
+
+    let prime_len = read_u32(&mut pos)? as usize;
+    if pos + prime_len > data.len() {
+        return Err("Unexpected end of data reading prime".to_string());
+    }
+    let prime = data[pos..pos + prime_len].to_vec();
+    pos += prime_len;
+
+    let n_wires = read_u32(&mut pos)?;
+    let n_pub_out = read_u32(&mut pos)?;
+    let n_pub_in = read_u32(&mut pos)?;
+    let n_constraints = read_u32(&mut pos)?;
+
+    let n_wire_labels = read_u32(&mut pos)? as usize;
+    // n_wire_labels is an untrusted u32 straight off the buffer; a crafted
+    // blob can claim e.g. u32::MAX to make an upfront Vec::with_capacity try
+    // to allocate gigabytes before a single label is read. Grow incrementally
+    // instead so a truncated/malicious blob fails on read_u64, not on
+    // allocation (same fix as zkenc-cli's r1cs.rs parser).
+    let mut wire_to_label = Vec::new();
+    for _ in 0..n_wire_labels {
+        wire_to_label.push(read_u64(&mut pos)?);
+    }
+
+    let header = R1csHeader {
+        field_size,
+        prime,
+        n_wires,
+        n_pub_out,
+        n_pub_in,
+        n_constraints,
+        wire_to_label,
+    };
+
+    let n_constraint_entries = read_u32(&mut pos)? as usize;
+    // Same reasoning as wire_to_label above: don't pre-reserve capacity for
+    // an untrusted count.
+    let mut constraints = Vec::new();
+    for _ in 0..n_constraint_entries {
+        let a_factors = decode_linear_combination(data, &mut pos)?;
+        let b_factors = decode_linear_combination(data, &mut pos)?;
+        let c_factors = decode_linear_combination(data, &mut pos)?;
+        constraints.push(R1csConstraint {
+            a_factors,
+            b_factors,
+            c_factors,
+        });
+    }
+
+    Ok((header, constraints))
+}
+
+fn decode_linear_combination(data: &[u8], pos: &mut usize) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    if *pos + 4 > data.len() {
+        return Err("Unexpected end of data in LC".to_string());
+    }
+    let n_factors =
+        u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+    *pos += 4;
+
+    // n_factors is an untrusted u32 straight off the buffer; don't
+    // pre-reserve capacity for it (same reasoning as wire_to_label above).
+    let mut factors = Vec::new();
+    for _ in 0..n_factors {
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data reading wire id".to_string());
+        }
+        let wire_id =
+            u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+        *pos += 4;
+
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data reading factor value length".to_string());
+        }
+        let value_len = u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]])
+            as usize;
+        *pos += 4;
+
+        if *pos + value_len > data.len() {
+            return Err("Unexpected end of data reading factor value".to_string());
+        }
+        let value = data[*pos..*pos + value_len].to_vec();
+        *pos += value_len;
+
+        factors.push((wire_id, value));
+    }
+
+    Ok(factors)
+}
+
+/// Parse a binary `.wtns` file (format version 2) into a vector of field
+/// elements, in the order the file stores them. Mapping each entry to a wire
+/// index is left to the caller (see [`R1csHeader::label_to_wire`]).
+pub fn parse_witness<F: PrimeField>(data: &[u8]) -> Result<Vec<F>, String> {
+    let mut pos = 0;
+
+    let read_u32 = |pos: &mut usize| -> Result<u32, String> {
+        if *pos + 4 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
+        *pos += 4;
+        Ok(val)
+    };
+
+    let read_u64 = |pos: &mut usize| -> Result<u64, String> {
+        if *pos + 8 > data.len() {
+            return Err("Unexpected end of data".to_string());
+        }
+        let val = u64::from_le_bytes([
+            data[*pos],
+            data[*pos + 1],
+            data[*pos + 2],
+            data[*pos + 3],
+            data[*pos + 4],
+            data[*pos + 5],
+            data[*pos + 6],
+            data[*pos + 7],
+        ]);
+        *pos += 8;
+        Ok(val)
+    };
+
+    // Check magic "wtns"
+    if pos + 4 > data.len() || &data[pos..pos + 4] != b"wtns" {
+        return Err("Invalid witness file: wrong magic".to_string());
+    }
+    pos += 4;
+
+    let version = read_u32(&mut pos)?;
+    if version != 2 {
+        return Err(format!("Unsupported witness version: {}", version));
+    }
+
+    let n_sections = read_u32(&mut pos)?;
+
+    let mut witness: Vec<F> = Vec::new();
+    let mut n8 = 0usize;
+
+    for _ in 0..n_sections {
+        let section_type = read_u32(&mut pos)?;
+        let section_len = read_u64(&mut pos)? as usize;
+
+        let section_end = pos
+            .checked_add(section_len)
+            .filter(|&end| end <= data.len())
+            .ok_or("Section length runs past end of data")?;
+
+        if section_type == 1 {
+            // Header section
+            n8 = read_u32(&mut pos)? as usize;
+            if n8 == 0 || n8 > 32 {
+                return Err(format!(
+                    "Unsupported witness field element size: {} bytes",
+                    n8
+                ));
+            }
+        } else if section_type == 2 {
+            // Witness values section - contains raw witness data (field_size * n_witness bytes)
+            if n8 == 0 {
+                return Err("Witness data section appeared before header section".to_string());
+            }
+            while pos + n8 <= section_end {
+                if pos + n8 > data.len() {
+                    return Err("Unexpected end of data reading witness value".to_string());
+                }
+                let mut bytes = [0u8; 32];
+                let copy_len = n8.min(32);
+                bytes[..copy_len].copy_from_slice(&data[pos..pos + copy_len]);
+
+                witness.push(F::from_le_bytes_mod_order(&bytes));
+                pos += n8;
+            }
+        }
+
+        pos = section_end;
+    }
+
+    Ok(witness)
+}
+
+/// A circom R1CS circuit together with a (possibly partial) witness
+/// assignment, ready to hand to `encap`/`decap` as a `ConstraintSynthesizer`.
+pub struct CircomCircuit<F: PrimeField> {
+    pub header: R1csHeader,
+    pub constraints: Vec<R1csConstraint>,
+    pub witness: HashMap<u32, F>,
+}
+
+impl<F: PrimeField> CircomCircuit<F> {
+    pub fn new(header: R1csHeader, constraints: Vec<R1csConstraint>, witness: HashMap<u32, F>) -> Self {
+        Self {
+            header,
+            constraints,
+            witness,
+        }
+    }
+
+    fn bytes_to_field(bytes: &[u8]) -> F {
+        let mut bytes_array = [0u8; 32];
+        let len = bytes.len().min(32);
+        bytes_array[..len].copy_from_slice(&bytes[..len]);
+        F::from_le_bytes_mod_order(&bytes_array)
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for CircomCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let mut variables: HashMap<u32, Variable> = HashMap::new();
+        variables.insert(0, Variable::One);
+
+        let n_public = self.header.n_public_inputs();
+        for wire_id in 1..=n_public {
+            let value = self.witness.get(&wire_id).copied();
+            let var = cs.new_input_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+            variables.insert(wire_id, var);
+        }
+
+        for wire_id in (n_public + 1)..self.header.n_wires {
+            let value = self.witness.get(&wire_id).copied();
+            let var = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+            variables.insert(wire_id, var);
+        }
+
+        for constraint in self.constraints {
+            let a_lc = build_lc::<F>(&constraint.a_factors, &variables);
+            let b_lc = build_lc::<F>(&constraint.b_factors, &variables);
+            let c_lc = build_lc::<F>(&constraint.c_factors, &variables);
+
+            let boxed: Vec<Box<dyn FnOnce() -> LinearCombination<F>>> = vec![
+                Box::new(move || a_lc),
+                Box::new(move || b_lc),
+                Box::new(move || c_lc),
+            ];
+            cs.enforce_constraint(R1CS_PREDICATE_LABEL, boxed)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn build_lc<F: PrimeField>(
+    factors: &[(u32, Vec<u8>)],
+    variables: &HashMap<u32, Variable>,
+) -> LinearCombination<F> {
+    let mut lc = LinearCombination::zero();
+    for (wire_id, coeff_bytes) in factors {
+        if let Some(&var) = variables.get(wire_id) {
+            let coeff = CircomCircuit::<F>::bytes_to_field(coeff_bytes);
+            lc = lc + (coeff, var);
+        }
+    }
+    lc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+
+    fn push_u32(out: &mut Vec<u8>, value: u32) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_u64(out: &mut Vec<u8>, value: u64) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Build a minimal one-constraint R1CS `1 * 1 = 1` (just the constant
+    /// wire, no public/private inputs) to exercise the parser end to end.
+    fn build_minimal_r1cs() -> Vec<u8> {
+        let field_size = 32u32;
+        let prime = {
+            let mut p = vec![0u8; 32];
+            p[0] = 1;
+            p
+        };
+
+        let mut header_section = Vec::new();
+        push_u32(&mut header_section, field_size);
+        header_section.extend_from_slice(&prime);
+        push_u32(&mut header_section, 1); // n_wires
+        push_u32(&mut header_section, 0); // n_pub_out
+        push_u32(&mut header_section, 0); // n_pub_in
+        push_u32(&mut header_section, 0); // n_prv_in
+        push_u64(&mut header_section, 0); // n_labels
+        push_u32(&mut header_section, 1); // n_constraints
+
+        let lc_one = |out: &mut Vec<u8>| {
+            push_u32(out, 1); // one factor
+            push_u32(out, 0); // wire 0
+            out.extend_from_slice(&{
+                let mut v = vec![0u8; 32];
+                v[0] = 1;
+                v
+            });
+        };
+        let mut constraints_section = Vec::new();
+        lc_one(&mut constraints_section);
+        lc_one(&mut constraints_section);
+        lc_one(&mut constraints_section);
+
+        let mut out = Vec::new();
+        out.extend_from_slice(b"r1cs");
+        push_u32(&mut out, 1); // version
+        push_u32(&mut out, 2); // n_sections
+
+        push_u32(&mut out, 0x01);
+        push_u64(&mut out, header_section.len() as u64);
+        out.extend_from_slice(&header_section);
+
+        push_u32(&mut out, 0x02);
+        push_u64(&mut out, constraints_section.len() as u64);
+        out.extend_from_slice(&constraints_section);
+
+        out
+    }
+
+    #[test]
+    fn test_parse_r1cs_minimal() {
+        let data = build_minimal_r1cs();
+        let (header, constraints) = parse_r1cs(&data).expect("should parse");
+
+        assert_eq!(header.n_wires, 1);
+        assert_eq!(header.n_constraints, 1);
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0].a_factors, vec![(0, {
+            let mut v = vec![0u8; 32];
+            v[0] = 1;
+            v
+        })]);
+    }
+
+    #[test]
+    fn test_parse_r1cs_rejects_bad_magic() {
+        let data = b"xxxx".to_vec();
+        assert!(parse_r1cs(&data).is_err());
+    }
+
+    #[test]
+    fn test_circom_circuit_synthesizes_minimal_r1cs() {
+        use ark_relations::gr1cs::ConstraintSystem;
+
+        let data = build_minimal_r1cs();
+        let (header, constraints) = parse_r1cs(&data).expect("should parse");
+        let circuit = CircomCircuit::<Fr>::new(header, constraints, HashMap::new());
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
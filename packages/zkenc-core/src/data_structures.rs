@@ -3,7 +3,8 @@
 //! This module defines the core data structures used in the WKEM scheme:
 //! - EncapKey: The Common Reference String (CRS) σ generated during Encap
 //! - Ciphertext: Contains EncapKey and public inputs
-//! - Key: The derived symmetric key (32 bytes from Blake3)
+//! - Key: The derived symmetric key (32 bytes, from HKDF-SHA256 by default -
+//!   see [`crate::algorithm::KeyDerivation`])
 
 use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
@@ -34,7 +35,8 @@ pub struct EncapKey<E: Pairing> {
     /// where φᵢ(x) = r·β·uᵢ(x) + r·α·vᵢ(x) + r²·wᵢ(x)
     pub phi_delta_query_g1: Vec<E::G1Affine>,
 
-    /// {[r²·xⁱ·t(x)/δ]₁}ᵢ₌₀^(n-2) - for computing quotient polynomial h(x)
+    /// {[xⁱ·t(x)/δ]₁}ᵢ₌₀^(n-2) - basis for committing the quotient
+    /// polynomial h(x) = (A(x)B(x) - C(x)) / t(x), deg(h) ≤ n-2
     pub h_query_g1: Vec<E::G1Affine>,
 }
 
@@ -48,7 +50,7 @@ pub struct Ciphertext<E: Pairing> {
     pub public_inputs: Vec<E::ScalarField>,
 }
 
-/// Derived symmetric key (output of Blake3 hash)
+/// Derived symmetric key (output of the [`crate::algorithm::KeyDerivation`] KDF)
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Key(pub [u8; 32]);
 
@@ -63,3 +65,13 @@ impl Key {
         &self.0
     }
 }
+
+// `EncapKey`/`Ciphertext` compact wire (de)serialization used to live here as
+// a hand-rolled write/read/to_bytes/from_bytes pair, but it had no callers
+// anywhere in the workspace - every real serialization path (commands.rs,
+// formats.rs, hybrid.rs) already goes through `serialize_compressed`/
+// `deserialize_compressed` on these structs' `CanonicalSerialize`/
+// `CanonicalDeserialize` derives above, which was a compact wire format
+// before this ever existed. It's been removed rather than kept as unused
+// library surface with an unfixed allocation bug (`m`/`h_len` were
+// attacker-controlled `u32`s fed straight into `Vec::with_capacity`).
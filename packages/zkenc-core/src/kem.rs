@@ -0,0 +1,55 @@
+//! Adapters implementing the RustCrypto [`kem`] crate's `Encapsulate`/
+//! `Decapsulate` traits on top of [`crate::algorithm::encap`]/
+//! [`crate::algorithm::decap`].
+//!
+//! These let downstream code compose WKEM with other KEMs and write generic
+//! protocol code against `kem`'s traits instead of calling `encap`/`decap`
+//! directly and going through the filesystem-oriented `zkenc-cli` commands.
+
+use ark_ec::pairing::Pairing;
+use ark_relations::gr1cs::ConstraintSynthesizer;
+use kem::{Decapsulate, Encapsulate};
+use rand_core::CryptoRngCore;
+
+use crate::algorithm::{decap, encap, Error};
+use crate::data_structures::{Ciphertext, Key};
+
+/// An encapsulation-key handle: a circuit with its public inputs assigned
+/// (witness left unassigned). Implements [`kem::Encapsulate`] by delegating
+/// to [`crate::algorithm::encap`].
+#[derive(Clone, Debug)]
+pub struct EncapsulatingKey<C>(pub C);
+
+impl<E, C> Encapsulate<Ciphertext<E>, Key> for EncapsulatingKey<C>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField> + Clone,
+{
+    type Error = Error;
+
+    fn encapsulate(
+        &self,
+        rng: &mut impl CryptoRngCore,
+    ) -> Result<(Ciphertext<E>, Key), Self::Error> {
+        encap(self.0.clone(), rng)
+    }
+}
+
+/// A witness-holding handle: a circuit with the full assignment (public
+/// inputs + witness). Implements [`kem::Decapsulate`] by delegating to
+/// [`crate::algorithm::decap`], so a wrong witness surfaces as
+/// [`Error::InvalidWitness`] instead of silently returning a mismatched key.
+#[derive(Clone, Debug)]
+pub struct DecapsulatingKey<C>(pub C);
+
+impl<E, C> Decapsulate<Ciphertext<E>, Key> for DecapsulatingKey<C>
+where
+    E: Pairing,
+    C: ConstraintSynthesizer<E::ScalarField> + Clone,
+{
+    type Error = Error;
+
+    fn decapsulate(&self, encapsulated_key: &Ciphertext<E>) -> Result<Key, Self::Error> {
+        decap(self.0.clone(), encapsulated_key)
+    }
+}
@@ -13,12 +13,32 @@ pub mod data_structures;
 /// Core algorithms module
 pub mod algorithm;
 
+/// Parsing for circom's `.r1cs`/`.wtns` binary formats and the circom
+/// `ConstraintSynthesizer` bridge into `encap`/`decap`, used by zkenc-js.
+/// zkenc-cli does not use it; it keeps its own parser.
+#[cfg(feature = "std")]
+pub mod circom;
+
 /// R1CS to QAP conversion utilities
 mod r1cs_to_qap;
 
+/// Multicore chunking helper backing the QAP subsystem's parallel loops
+mod worker;
+
+/// `kem` crate trait adapters for `encap`/`decap`
+pub mod kem;
+
+/// Poseidon/Hades sponge and a field-native duplex cipher built on it
+pub mod poseidon;
+
 // Re-export commonly used types
-pub use algorithm::{decap, encap, verify_ciphertext, Error};
+pub use algorithm::{
+    decap, decap_with_kdf, encap, encap_with_kdf, verify_ciphertext, DefaultKdf, Error,
+    KeyDerivation,
+};
 pub use data_structures::{Ciphertext, EncapKey, Key};
+pub use kem::{DecapsulatingKey, EncapsulatingKey};
+pub use poseidon::{decrypt_field, encrypt_field, PoseidonConfig};
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,281 @@
+//! A Poseidon/Hades sponge over `E::ScalarField`, and a duplex-mode
+//! authenticated cipher built on top of it.
+//!
+//! Unlike byte ciphers such as zkenc-cli's `crypto` module, this keeps the entire
+//! plaintext/ciphertext inside the curve's scalar field, so an encrypted
+//! value can be referenced by a later circuit's constraints without first
+//! crossing back out to bytes.
+//!
+//! # Parameters
+//!
+//! The permutation runs over a width-2 state (`rate = 1`, `capacity = 1`)
+//! with the standard Hades round structure: `R_F = 8` full rounds (4 before
+//! the partial rounds, 4 after) in which the S-box `x -> x^5` is applied to
+//! every lane, and `R_P = 57` partial rounds in which it is applied only to
+//! lane 0. Round constants are generated deterministically by expanding the
+//! domain string `"zkenc-poseidon-v1"` through SHA-256 (one digest per
+//! lane, reduced mod the field's order); the MDS matrix is the 2x2 Cauchy
+//! matrix over `{1, 2}` x `{3, 4}`, which is MDS for any prime field whose
+//! order exceeds 6 (the largest `x_i + y_j`). These are this crate's own
+//! parameters, not the reference Poseidon paper's published constants -
+//! swap in audited constants here if this is ever used outside zkenc.
+use ark_ff::{Field, PrimeField};
+use ark_serialize::CanonicalDeserialize;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use crate::algorithm::Error;
+use crate::data_structures::Key;
+
+const WIDTH: usize = 2;
+const RATE_IDX: usize = 1;
+const CAPACITY_IDX: usize = 0;
+const ALPHA: u64 = 5;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+const DOMAIN: &[u8] = b"zkenc-poseidon-v1";
+
+/// A Hades permutation's fixed parameters: round constants and MDS matrix,
+/// generated once per scalar field `F` and reused for every permutation call.
+pub struct PoseidonConfig<F: PrimeField> {
+    round_constants: Vec<[F; WIDTH]>,
+    mds: [[F; WIDTH]; WIDTH],
+}
+
+fn hash_to_field<F: PrimeField>(counter: u64) -> F {
+    let mut hasher = Sha256::new();
+    hasher.update(DOMAIN);
+    hasher.update(counter.to_le_bytes());
+    let digest = hasher.finalize();
+    F::from_le_bytes_mod_order(&digest)
+}
+
+fn generate_round_constants<F: PrimeField>() -> Vec<[F; WIDTH]> {
+    let total_rounds = FULL_ROUNDS + PARTIAL_ROUNDS;
+    let mut counter = 0u64;
+    let mut constants = Vec::with_capacity(total_rounds);
+    for _ in 0..total_rounds {
+        let mut round = [F::zero(); WIDTH];
+        for lane in round.iter_mut() {
+            *lane = hash_to_field::<F>(counter);
+            counter += 1;
+        }
+        constants.push(round);
+    }
+    constants
+}
+
+/// The 2x2 Cauchy matrix `mds[i][j] = 1 / (x_i + y_j)` over `x = {1, 2}`,
+/// `y = {3, 4}`. Cauchy matrices built from two disjoint sets of distinct
+/// elements are MDS over any field in which every `x_i + y_j` is non-zero -
+/// true here as long as the field's characteristic exceeds 6 (the sums are
+/// 4, 5, 5 and 6).
+fn generate_mds<F: PrimeField>() -> [[F; WIDTH]; WIDTH] {
+    let xs = [F::from(1u64), F::from(2u64)];
+    let ys = [F::from(3u64), F::from(4u64)];
+    let mut mds = [[F::zero(); WIDTH]; WIDTH];
+    for i in 0..WIDTH {
+        for j in 0..WIDTH {
+            let denom = xs[i] + ys[j];
+            mds[i][j] = denom
+                .inverse()
+                .expect("x_i + y_j is never zero by construction");
+        }
+    }
+    mds
+}
+
+impl<F: PrimeField> PoseidonConfig<F> {
+    /// Build this field's Poseidon parameters (see the module docs for the
+    /// fixed width/round counts and how constants are derived).
+    pub fn new() -> Self {
+        Self {
+            round_constants: generate_round_constants::<F>(),
+            mds: generate_mds::<F>(),
+        }
+    }
+}
+
+impl<F: PrimeField> Default for PoseidonConfig<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn apply_mds<F: PrimeField>(mds: &[[F; WIDTH]; WIDTH], state: &[F; WIDTH]) -> [F; WIDTH] {
+    let mut out = [F::zero(); WIDTH];
+    for i in 0..WIDTH {
+        let mut acc = F::zero();
+        for j in 0..WIDTH {
+            acc += mds[i][j] * state[j];
+        }
+        out[i] = acc;
+    }
+    out
+}
+
+/// Run the full Hades permutation over `state` in place.
+pub fn permute<F: PrimeField>(config: &PoseidonConfig<F>, state: &mut [F; WIDTH]) {
+    let half_full = FULL_ROUNDS / 2;
+    let mut round = 0usize;
+
+    for _ in 0..half_full {
+        for (lane, constant) in state.iter_mut().zip(config.round_constants[round].iter()) {
+            *lane += *constant;
+            *lane = lane.pow([ALPHA]);
+        }
+        *state = apply_mds(&config.mds, state);
+        round += 1;
+    }
+
+    for _ in 0..PARTIAL_ROUNDS {
+        for (lane, constant) in state.iter_mut().zip(config.round_constants[round].iter()) {
+            *lane += *constant;
+        }
+        state[0] = state[0].pow([ALPHA]);
+        *state = apply_mds(&config.mds, state);
+        round += 1;
+    }
+
+    for _ in 0..half_full {
+        for (lane, constant) in state.iter_mut().zip(config.round_constants[round].iter()) {
+            *lane += *constant;
+            *lane = lane.pow([ALPHA]);
+        }
+        *state = apply_mds(&config.mds, state);
+        round += 1;
+    }
+}
+
+/// Reduce a 32-byte [`Key`] to the two field elements used to initialize
+/// the sponge's capacity and rate lanes (each half of the key, reduced mod
+/// the field's order).
+fn key_to_lanes<F: PrimeField>(key: &Key) -> (F, F) {
+    let bytes = key.as_bytes();
+    (
+        F::from_le_bytes_mod_order(&bytes[0..16]),
+        F::from_le_bytes_mod_order(&bytes[16..32]),
+    )
+}
+
+fn init_state<F: PrimeField>(config: &PoseidonConfig<F>, key: &Key, nonce: F) -> [F; WIDTH] {
+    let (k0, k1) = key_to_lanes::<F>(key);
+    let mut state = [F::zero(); WIDTH];
+    state[CAPACITY_IDX] = k0;
+    state[RATE_IDX] = k1 + nonce;
+    permute(config, &mut state);
+    state
+}
+
+/// Encrypt `plaintext` under `key` with a fresh random nonce, via a Poseidon
+/// duplex: each plaintext element is added to a freshly-squeezed state
+/// element to produce the matching ciphertext element, which is then fed
+/// back into the rate lane before the next permutation so every ciphertext
+/// element is bound into the final tag.
+///
+/// Returns `[nonce, c_1, ..., c_n, tag]` as a single vector, ready to
+/// `CanonicalSerialize` alongside the witness-KEM [`crate::Ciphertext`].
+pub fn encrypt_field<F: PrimeField>(
+    key: &Key,
+    plaintext: &[F],
+    rng: &mut impl RngCore,
+) -> Vec<F> {
+    let config = PoseidonConfig::<F>::new();
+    let nonce = F::rand(rng);
+    let mut state = init_state(&config, key, nonce);
+
+    let mut out = Vec::with_capacity(plaintext.len() + 2);
+    out.push(nonce);
+    for &p in plaintext {
+        permute(&config, &mut state);
+        let c = p + state[RATE_IDX];
+        state[RATE_IDX] = c;
+        out.push(c);
+    }
+    permute(&config, &mut state);
+    out.push(state[RATE_IDX]);
+
+    out
+}
+
+/// Decrypt a `[nonce, c_1, ..., c_n, tag]` vector produced by
+/// [`encrypt_field`], reversing the additions and re-deriving the tag to
+/// detect tampering. Returns [`Error::InvalidWitness`] if the tag doesn't
+/// match.
+pub fn decrypt_field<F: PrimeField>(key: &Key, data: &[F]) -> Result<Vec<F>, Error> {
+    if data.len() < 2 {
+        return Err(Error::InvalidPublicInputs);
+    }
+    let nonce = data[0];
+    let tag = data[data.len() - 1];
+    let ciphertext = &data[1..data.len() - 1];
+
+    let config = PoseidonConfig::<F>::new();
+    let mut state = init_state(&config, key, nonce);
+
+    let mut out = Vec::with_capacity(ciphertext.len());
+    for &c in ciphertext {
+        permute(&config, &mut state);
+        let p = c - state[RATE_IDX];
+        state[RATE_IDX] = c;
+        out.push(p);
+    }
+    permute(&config, &mut state);
+
+    if state[RATE_IDX] != tag {
+        return Err(Error::InvalidWitness);
+    }
+
+    Ok(out)
+}
+
+/// Deserialize a `[nonce, c_1, ..., c_n, tag]` vector written by
+/// [`encrypt_field`]'s `CanonicalSerialize` encoding.
+pub fn deserialize_field_ciphertext<F: PrimeField>(data: &[u8]) -> Result<Vec<F>, Error> {
+    Vec::<F>::deserialize_compressed(data).map_err(|_| Error::SerializationError)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let key = Key::new([7u8; 32]);
+        let plaintext: Vec<Fr> = (0..5).map(Fr::from).collect();
+
+        let ciphertext = encrypt_field(&key, &plaintext, &mut rng);
+        let recovered = decrypt_field::<Fr>(&key, &ciphertext).unwrap();
+
+        assert_eq!(recovered, plaintext);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_tampered_ciphertext() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let key = Key::new([9u8; 32]);
+        let plaintext: Vec<Fr> = (0..3).map(Fr::from).collect();
+
+        let mut ciphertext = encrypt_field(&key, &plaintext, &mut rng);
+        let mid = ciphertext.len() / 2;
+        ciphertext[mid] += Fr::from(1u64);
+
+        assert!(decrypt_field::<Fr>(&key, &ciphertext).is_err());
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let key = Key::new([1u8; 32]);
+        let wrong_key = Key::new([2u8; 32]);
+        let plaintext: Vec<Fr> = (0..4).map(Fr::from).collect();
+
+        let ciphertext = encrypt_field(&key, &plaintext, &mut rng);
+
+        assert!(decrypt_field::<Fr>(&wrong_key, &ciphertext).is_err());
+    }
+}
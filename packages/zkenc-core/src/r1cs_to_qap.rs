@@ -8,6 +8,39 @@ use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_relations::gr1cs::ConstraintSystemRef;
 use ark_std::vec::Vec;
 
+use crate::worker;
+
+/// Errors building the QAP evaluation domain or evaluating the QAP
+/// polynomials over it.
+#[derive(Debug, PartialEq, Eq)]
+pub enum QapError {
+    /// The domain size (the next power of two at or above
+    /// [`qap_domain_size`]) exceeds the scalar field's two-adicity, so no
+    /// root of unity of that order exists - mirrors bellman's
+    /// `SynthesisError::PolynomialDegreeTooLarge`.
+    PolynomialDegreeTooLarge,
+    /// The constraint system's R1CS matrices could not be extracted
+    /// (`ConstraintSystemRef::to_matrices` failed).
+    MatricesUnavailable,
+    /// The extracted matrices didn't contain the three R1CS predicate rows
+    /// (A, B, C) a QAP reduction needs.
+    MalformedMatrices,
+}
+
+impl core::fmt::Display for QapError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            QapError::PolynomialDegreeTooLarge => {
+                write!(f, "QAP domain size exceeds the scalar field's two-adicity")
+            }
+            QapError::MatricesUnavailable => write!(f, "Constraint system matrices are unavailable"),
+            QapError::MalformedMatrices => {
+                write!(f, "Constraint system matrices are malformed (missing A/B/C rows)")
+            }
+        }
+    }
+}
+
 /// Get the number of public inputs (ℓ in the scheme)
 pub fn num_public_inputs<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> usize {
     cs.num_instance_variables()
@@ -30,18 +63,117 @@ pub fn num_constraints<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> usize {
 
 /// Create an evaluation domain for QAP
 ///
-/// The domain size must be at least the number of constraints.
+/// The domain size must be at least the number of constraints. Fails with
+/// [`QapError::PolynomialDegreeTooLarge`] if `num_constraints` (rounded up
+/// to the next power of two) exceeds the field's two-adicity.
 /// This function is reserved for future full QAP conversion implementation.
 #[allow(dead_code)]
-pub fn create_domain<F: PrimeField>(num_constraints: usize) -> GeneralEvaluationDomain<F> {
-    GeneralEvaluationDomain::<F>::new(num_constraints).expect("Failed to create evaluation domain")
+pub fn create_domain<F: PrimeField>(
+    num_constraints: usize,
+) -> Result<GeneralEvaluationDomain<F>, QapError> {
+    GeneralEvaluationDomain::<F>::new(num_constraints).ok_or(QapError::PolynomialDegreeTooLarge)
+}
+
+/// Size of the QAP evaluation domain: `num_constraints` rows for the R1CS
+/// constraints themselves, plus one row per public input. Only the
+/// constraint rows are filled by [`evaluate_qap_polynomials_at_x`] and
+/// [`compute_h_coefficients`]; the extra rows stay zero, so no
+/// `aᵢ · 1 = aᵢ` row is added for the public inputs.
+/// `GeneralEvaluationDomain` rounds this up to the next power of two.
+pub fn qap_domain_size<F: PrimeField>(cs: &ConstraintSystemRef<F>) -> usize {
+    num_constraints(cs) + num_public_inputs(cs)
+}
+
+fn dot_product<F: PrimeField>(row: &[(F, usize)], assignment: &[F]) -> F {
+    row.iter().fold(F::zero(), |acc, &(coeff, index)| {
+        if index < assignment.len() {
+            acc + coeff * assignment[index]
+        } else {
+            acc
+        }
+    })
+}
+
+/// Compute the QAP quotient polynomial's coefficients,
+/// `h(X) = (A(X)·B(X) - C(X)) / t(X)`, for a full variable assignment
+/// (public inputs + witness).
+///
+/// `A(X) = Σᵢ aᵢuᵢ(X)`, `B(X) = Σᵢ aᵢvᵢ(X)`, `C(X) = Σᵢ aᵢwᵢ(X)` where `uᵢ,
+/// vᵢ, wᵢ` are each interpolated through the evaluation domain's points from
+/// the R1CS constraint matrices. Since the QAP relation makes `A·B - C`
+/// vanish on the domain, it's a multiple of the vanishing polynomial
+/// `t(X) = X^n - 1`; dividing it out gives `h`.
+///
+/// Evaluating `A`, `B`, `C` directly on the domain would make `t` (and so
+/// the division) undefined there, so this evaluates on a coset instead: an
+/// inverse FFT recovers `A`, `B`, `C`'s coefficients from their domain
+/// evaluations, a coset FFT re-evaluates them where `t` is never zero, the
+/// pointwise quotient is taken there, and a coset inverse FFT converts the
+/// result back to `h`'s coefficients. `GeneralEvaluationDomain` rounds
+/// [`qap_domain_size`] up to the smallest power of two that fits it and
+/// picks the primitive root of unity and coset offset (the field's
+/// multiplicative generator) for us, so none of that needs to be driven by
+/// hand here.
+///
+/// Returns an empty vector if the constraint matrices or evaluation domain
+/// (e.g. a domain size beyond the field's two-adicity) aren't available.
+pub fn compute_h_coefficients<F: PrimeField>(
+    cs: &ConstraintSystemRef<F>,
+    full_assignment: &[F],
+) -> Vec<F> {
+    use ark_relations::gr1cs::R1CS_PREDICATE_LABEL;
+
+    let domain = match GeneralEvaluationDomain::<F>::new(qap_domain_size(cs)) {
+        Some(d) => d,
+        None => return Vec::new(),
+    };
+    let n = domain.size();
+
+    let matrices = match cs.to_matrices() {
+        Ok(m) => m,
+        Err(_) => return Vec::new(),
+    };
+    let constraint_matrices = &matrices[R1CS_PREDICATE_LABEL];
+    if constraint_matrices.len() < 3 {
+        return Vec::new();
+    }
+
+    let mut a_evals = vec![F::zero(); n];
+    let mut b_evals = vec![F::zero(); n];
+    let mut c_evals = vec![F::zero(); n];
+
+    for j in 0..num_constraints(cs) {
+        a_evals[j] = dot_product(&constraint_matrices[0][j], full_assignment);
+        b_evals[j] = dot_product(&constraint_matrices[1][j], full_assignment);
+        c_evals[j] = dot_product(&constraint_matrices[2][j], full_assignment);
+    }
+
+    domain.ifft_in_place(&mut a_evals);
+    domain.ifft_in_place(&mut b_evals);
+    domain.ifft_in_place(&mut c_evals);
+
+    domain.coset_fft_in_place(&mut a_evals);
+    domain.coset_fft_in_place(&mut b_evals);
+    domain.coset_fft_in_place(&mut c_evals);
+
+    let mut ab_minus_c: Vec<F> = a_evals
+        .iter()
+        .zip(b_evals.iter())
+        .zip(c_evals.iter())
+        .map(|((a, b), c)| *a * b - c)
+        .collect();
+
+    domain.divide_by_vanishing_poly_on_coset_in_place(&mut ab_minus_c);
+    domain.coset_ifft_in_place(&mut ab_minus_c);
+
+    ab_minus_c
 }
 
 /// Evaluate all QAP polynomials at point x
 ///
 /// For each variable i, evaluates:
 /// - u_i(x): Left constraint polynomial
-/// - v_i(x): Right constraint polynomial  
+/// - v_i(x): Right constraint polynomial
 /// - w_i(x): Output constraint polynomial
 ///
 /// Returns (u_evals, v_evals, w_evals) where each is a Vec of evaluations
@@ -51,74 +183,91 @@ pub fn create_domain<F: PrimeField>(num_constraints: usize) -> GeneralEvaluation
 /// 2. Evaluate all Lagrange basis polynomials L_j(x) at x
 /// 3. For each variable i, compute uᵢ(x) = Σⱼ L_j(x) * A[j][i]
 ///    where A[j][i] is the coefficient of variable i in constraint j
+///
+/// Fails with [`QapError::MatricesUnavailable`]/[`QapError::MalformedMatrices`]
+/// if the constraint system's matrices can't be extracted or don't have the
+/// expected A/B/C rows, and with [`QapError::PolynomialDegreeTooLarge`] if
+/// the required domain size exceeds the field's two-adicity - a circuit
+/// this large can't be supported at all, so silently returning zeros (as
+/// this used to) would produce a bogus CRS instead of a visible failure.
 pub fn evaluate_qap_polynomials_at_x<F: PrimeField>(
     cs: &ConstraintSystemRef<F>,
     x: F,
-) -> (Vec<F>, Vec<F>, Vec<F>) {
+) -> Result<(Vec<F>, Vec<F>, Vec<F>), QapError> {
     use ark_relations::gr1cs::R1CS_PREDICATE_LABEL;
 
     let m = num_variables(cs);
 
     // Get constraint matrices
-    let matrices = match cs.to_matrices() {
-        Ok(m) => m,
-        Err(_) => {
-            // If matrices unavailable, return zeros
-            return (vec![F::zero(); m], vec![F::zero(); m], vec![F::zero(); m]);
-        }
-    };
+    let matrices = cs.to_matrices().map_err(|_| QapError::MatricesUnavailable)?;
 
     let constraint_matrices = &matrices[R1CS_PREDICATE_LABEL];
     if constraint_matrices.len() < 3 {
-        // Invalid matrices, return zeros
-        return (vec![F::zero(); m], vec![F::zero(); m], vec![F::zero(); m]);
+        return Err(QapError::MalformedMatrices);
     }
 
     // Create evaluation domain
-    // Domain size = num_constraints + num_instance_variables
-    let domain_size = num_constraints(cs) + num_public_inputs(cs);
-    let domain = match GeneralEvaluationDomain::<F>::new(domain_size) {
-        Some(d) => d,
-        None => {
-            // Domain too large, return zeros
-            return (vec![F::zero(); m], vec![F::zero(); m], vec![F::zero(); m]);
-        }
-    };
+    let domain = GeneralEvaluationDomain::<F>::new(qap_domain_size(cs))
+        .ok_or(QapError::PolynomialDegreeTooLarge)?;
 
     // Evaluate all Lagrange basis polynomials at x
     // L_j(x) is the unique polynomial that equals 1 at ω^j and 0 at all other domain points
     let lagrange_coeffs = domain.evaluate_all_lagrange_coefficients(x);
 
-    // Initialize result vectors
-    let mut u_evals = vec![F::zero(); m];
-    let mut v_evals = vec![F::zero(); m];
-    let mut w_evals = vec![F::zero(); m];
-
     // For each constraint j, accumulate L_j(x) * matrix[j][i] into result[i]
-    // This computes uᵢ(x) = Σⱼ L_j(x) * A[j][i] for all variables i
+    // This computes uᵢ(x) = Σⱼ L_j(x) * A[j][i] for all variables i. The
+    // constraint range is split into chunks (see `crate::worker`) so large
+    // circuits - the signature fixture's 8443 constraints and beyond -
+    // spread this accumulation across every available core instead of
+    // running it as one long serial loop.
     let n_constraints = num_constraints(cs);
-    for (j, &lagrange_j) in lagrange_coeffs.iter().enumerate().take(n_constraints) {
-        // Matrix A (corresponds to u polynomials)
-        for &(ref coeff, index) in &constraint_matrices[0][j] {
-            if index < m {
-                u_evals[index] += lagrange_j * coeff;
+    let identity = (vec![F::zero(); m], vec![F::zero(); m], vec![F::zero(); m]);
+
+    let accumulate_chunk = |start: usize, end: usize| -> (Vec<F>, Vec<F>, Vec<F>) {
+        let mut u_evals = vec![F::zero(); m];
+        let mut v_evals = vec![F::zero(); m];
+        let mut w_evals = vec![F::zero(); m];
+
+        for j in start..end {
+            let lagrange_j = lagrange_coeffs[j];
+
+            // Matrix A (corresponds to u polynomials)
+            for &(ref coeff, index) in &constraint_matrices[0][j] {
+                if index < m {
+                    u_evals[index] += lagrange_j * coeff;
+                }
             }
-        }
 
-        // Matrix B (corresponds to v polynomials)
-        for &(ref coeff, index) in &constraint_matrices[1][j] {
-            if index < m {
-                v_evals[index] += lagrange_j * coeff;
+            // Matrix B (corresponds to v polynomials)
+            for &(ref coeff, index) in &constraint_matrices[1][j] {
+                if index < m {
+                    v_evals[index] += lagrange_j * coeff;
+                }
             }
-        }
 
-        // Matrix C (corresponds to w polynomials)
-        for &(ref coeff, index) in &constraint_matrices[2][j] {
-            if index < m {
-                w_evals[index] += lagrange_j * coeff;
+            // Matrix C (corresponds to w polynomials)
+            for &(ref coeff, index) in &constraint_matrices[2][j] {
+                if index < m {
+                    w_evals[index] += lagrange_j * coeff;
+                }
             }
         }
-    }
 
-    (u_evals, v_evals, w_evals)
+        (u_evals, v_evals, w_evals)
+    };
+
+    let reduce_chunks =
+        |(mut au, mut av, mut aw): (Vec<F>, Vec<F>, Vec<F>), (bu, bv, bw): (Vec<F>, Vec<F>, Vec<F>)| {
+            for i in 0..m {
+                au[i] += bu[i];
+                av[i] += bv[i];
+                aw[i] += bw[i];
+            }
+            (au, av, aw)
+        };
+
+    let (u_evals, v_evals, w_evals) =
+        worker::chunked_map_reduce(n_constraints, identity, accumulate_chunk, reduce_chunks);
+
+    Ok((u_evals, v_evals, w_evals))
 }
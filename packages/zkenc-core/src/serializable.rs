@@ -27,6 +27,22 @@ pub struct SerializableConstraint {
     pub c: SerializableLC,
 }
 
+/// 自訂閘 (custom gate) 模板 - 對應 R1CS v2 的 "custom gates used list" 區段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableCustomGate {
+    pub template_name: String,
+    /// 模板參數,每個為小端序位元組表示的 field element
+    pub parameters: Vec<Vec<u8>>,
+}
+
+/// 自訂閘的單次套用 - 對應 R1CS v2 的 "custom gates applied" 區段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableCustomGateApplication {
+    /// 索引進入 `SerializableCircuit::custom_gates`
+    pub custom_gate_id: u32,
+    pub signals: Vec<u32>,
+}
+
 /// 完整的電路定義 - 對應 R1CS 格式
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SerializableCircuit {
@@ -56,6 +72,14 @@ pub struct SerializableCircuit {
     
     /// 變數標籤 (可選,用於除錯)
     pub wire_labels: Option<HashMap<u32, String>>,
+
+    /// R1CS v2 的自訂閘模板列表 (v1 電路或沒有自訂閘的 v2 電路為空)
+    #[serde(default)]
+    pub custom_gates: Vec<SerializableCustomGate>,
+
+    /// R1CS v2 的自訂閘套用列表 (v1 電路或沒有自訂閘的 v2 電路為空)
+    #[serde(default)]
+    pub custom_gate_applications: Vec<SerializableCustomGateApplication>,
 }
 
 /// 見證數據 - 所有變數的賦值
@@ -122,6 +146,130 @@ impl SerializableCircuit {
         let bytes = std::fs::read(path)?;
         Ok(Self::from_bincode(&bytes)?)
     }
+
+    /// 從 circom 匯出的 `circuit.json` 約束格式載入
+    ///
+    /// 該格式是一個頂層物件,包含:
+    /// - `constraints`: `[mapA, mapB, mapC]` 三元組陣列,每個 map 為
+    ///   `{ "wireIndexAsString": "decimalCoefficient" }`
+    /// - `nPubInputs`、`nOutputs`、`nVars`
+    ///
+    /// 沒有 `.r1cs` 檔案頭提供的 field byte 寬度,因此係數一律補齊為
+    /// [`CONSTRAINTS_JSON_FIELD_SIZE`] 位元組 (circom 預設的 BN254 scalar
+    /// field 大小)。
+    pub fn from_constraints_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let obj = value
+            .as_object()
+            .ok_or("constraints.json must be a JSON object")?;
+
+        let constraints_json = obj
+            .get("constraints")
+            .and_then(|v| v.as_array())
+            .ok_or("constraints.json is missing a 'constraints' array")?;
+
+        let mut constraints = Vec::with_capacity(constraints_json.len());
+        for (i, triple) in constraints_json.iter().enumerate() {
+            let maps = triple
+                .as_array()
+                .ok_or_else(|| format!("constraint {i} is not a [mapA, mapB, mapC] triple"))?;
+            if maps.len() != 3 {
+                return Err(format!("constraint {i} does not have exactly 3 maps").into());
+            }
+            constraints.push(SerializableConstraint {
+                a: parse_constraint_lc(&maps[0])?,
+                b: parse_constraint_lc(&maps[1])?,
+                c: parse_constraint_lc(&maps[2])?,
+            });
+        }
+
+        let n_pub_in = obj
+            .get("nPubInputs")
+            .and_then(|v| v.as_u64())
+            .ok_or("constraints.json is missing 'nPubInputs'")? as u32;
+        let n_pub_out = obj
+            .get("nOutputs")
+            .and_then(|v| v.as_u64())
+            .ok_or("constraints.json is missing 'nOutputs'")? as u32;
+        let n_wires = obj
+            .get("nVars")
+            .and_then(|v| v.as_u64())
+            .ok_or("constraints.json is missing 'nVars'")? as u32;
+
+        Ok(SerializableCircuit {
+            field_size: CONSTRAINTS_JSON_FIELD_SIZE,
+            prime_bytes: Vec::new(),
+            n_wires,
+            n_pub_out,
+            n_pub_in,
+            n_prv_in: n_wires.saturating_sub(1 + n_pub_in + n_pub_out),
+            n_constraints: constraints.len() as u32,
+            constraints,
+            wire_labels: None,
+            custom_gates: Vec::new(),
+            custom_gate_applications: Vec::new(),
+        })
+    }
+
+    /// 從 circom `circuit.json` 檔案載入
+    pub fn load_constraints_json(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        Self::from_constraints_json(&json)
+    }
+}
+
+/// circom `circuit.json` 沒有提供 field byte 寬度,補齊係數用的預設寬度
+/// (BN254/BLS12-381 scalar field 皆為 32 bytes)。
+const CONSTRAINTS_JSON_FIELD_SIZE: u32 = 32;
+
+/// 將一個 `{ "wireIndexAsString": "decimalCoefficient" }` map 轉成
+/// `SerializableLC`。
+fn parse_constraint_lc(value: &serde_json::Value) -> Result<SerializableLC, Box<dyn std::error::Error>> {
+    let map = value
+        .as_object()
+        .ok_or("expected a wire-id -> coefficient map")?;
+
+    let mut factors = Vec::with_capacity(map.len());
+    for (wire_id_str, coeff_value) in map {
+        let wire_id: u32 = wire_id_str
+            .parse()
+            .map_err(|_| format!("invalid wire id '{wire_id_str}'"))?;
+        let coeff_str = coeff_value
+            .as_str()
+            .ok_or_else(|| format!("coefficient for wire {wire_id} is not a decimal string"))?;
+        let coefficient_bytes =
+            decimal_str_to_le_bytes(coeff_str, CONSTRAINTS_JSON_FIELD_SIZE as usize)?;
+        factors.push(SerializableFactor {
+            wire_id,
+            coefficient_bytes,
+        });
+    }
+    Ok(SerializableLC { factors })
+}
+
+/// 將十進位字串轉成定長的小端序位元組 (不足補零)。
+fn decimal_str_to_le_bytes(s: &str, width: usize) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut bytes = vec![0u8];
+    for ch in s.trim().chars() {
+        let digit = ch
+            .to_digit(10)
+            .ok_or_else(|| format!("invalid decimal coefficient '{s}'"))?;
+        let mut carry = digit;
+        for byte in bytes.iter_mut() {
+            let v = (*byte as u32) * 10 + carry;
+            *byte = (v & 0xff) as u8;
+            carry = v >> 8;
+        }
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+    if bytes.len() > width {
+        return Err(format!("coefficient '{s}' does not fit in {width} bytes").into());
+    }
+    bytes.resize(width, 0);
+    Ok(bytes)
 }
 
 impl SerializableWitness {
@@ -180,6 +328,8 @@ mod tests {
             n_constraints: 50,
             constraints: vec![],
             wire_labels: None,
+            custom_gates: vec![],
+            custom_gate_applications: vec![],
         };
         
         // 測試 JSON 序列化
@@ -221,6 +371,8 @@ mod tests {
             n_constraints: 50,
             constraints: vec![],
             wire_labels: None,
+            custom_gates: vec![],
+            custom_gate_applications: vec![],
         };
         
         // 測試 bincode 序列化
@@ -230,4 +382,51 @@ mod tests {
         assert_eq!(loaded.field_size, 32);
         assert_eq!(loaded.n_wires, 100);
     }
+
+    #[test]
+    fn test_from_constraints_json() {
+        let json = r#"{
+            "nPubInputs": 1,
+            "nOutputs": 1,
+            "nVars": 4,
+            "constraints": [
+                [
+                    { "1": "1" },
+                    { "2": "1" },
+                    { "3": "21888242871839275222246405745257275088548364400416034343698204186575808495616" }
+                ]
+            ]
+        }"#;
+
+        let circuit = SerializableCircuit::from_constraints_json(json).unwrap();
+
+        assert_eq!(circuit.field_size, 32);
+        assert_eq!(circuit.n_pub_in, 1);
+        assert_eq!(circuit.n_pub_out, 1);
+        assert_eq!(circuit.n_wires, 4);
+        assert_eq!(circuit.n_constraints, 1);
+
+        let constraint = &circuit.constraints[0];
+        assert_eq!(constraint.a.factors.len(), 1);
+        assert_eq!(constraint.a.factors[0].wire_id, 1);
+        assert_eq!(
+            constraint.a.factors[0].coefficient_bytes,
+            {
+                let mut bytes = vec![0u8; 32];
+                bytes[0] = 1;
+                bytes
+            }
+        );
+
+        // -1 mod p, the largest representable coefficient, must round-trip
+        // through exactly 32 bytes without overflowing.
+        assert_eq!(constraint.c.factors[0].coefficient_bytes.len(), 32);
+        assert_eq!(constraint.c.factors[0].coefficient_bytes[31], 0x30);
+    }
+
+    #[test]
+    fn test_from_constraints_json_rejects_missing_fields() {
+        let json = r#"{ "constraints": [] }"#;
+        assert!(SerializableCircuit::from_constraints_json(json).is_err());
+    }
 }
@@ -0,0 +1,70 @@
+//! worker.rs - small multicore chunking helper for the QAP subsystem
+//!
+//! Mirrors bellman's `multicore::Worker`: splits a range of work into
+//! per-thread chunks, runs a closure over each chunk to produce a partial
+//! result, and folds the partial results back together. Feature-gated
+//! behind `parallel` (backed by rayon) so `no_std` / no-rayon builds keep
+//! a single serial chunk covering the whole range instead.
+//!
+//! This only covers work this crate owns the loop for (currently the QAP
+//! constraint-accumulation loop in [`crate::r1cs_to_qap`]) - the FFT
+//! butterflies `compute_h_coefficients` drives via `ark_poly`'s
+//! `EvaluationDomain` already have their own multicore path behind
+//! `ark-poly`'s own `parallel` feature, since that loop lives in that
+//! dependency rather than here.
+
+use ark_std::vec::Vec;
+
+/// Split `len` items of work into chunk `(start, end)` bounds: one chunk
+/// per available thread when `parallel` is enabled, or a single chunk
+/// covering the whole range otherwise.
+#[cfg(feature = "parallel")]
+fn chunk_bounds(len: usize) -> Vec<(usize, usize)> {
+    if len == 0 {
+        return Vec::new();
+    }
+    let num_chunks = rayon::current_num_threads().max(1).min(len);
+    let chunk_size = len.div_ceil(num_chunks);
+    (0..len)
+        .step_by(chunk_size)
+        .map(|start| (start, (start + chunk_size).min(len)))
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn chunk_bounds(len: usize) -> Vec<(usize, usize)> {
+    if len == 0 {
+        Vec::new()
+    } else {
+        ark_std::vec![(0, len)]
+    }
+}
+
+/// Run `f(start, end)` over each chunk of `0..len` - across rayon's thread
+/// pool when the `parallel` feature is enabled, or as a single serial call
+/// over the full range otherwise - then fold the per-chunk results
+/// together with `reduce`, seeded by `identity`.
+pub fn chunked_map_reduce<T, F, R>(len: usize, identity: T, f: F, reduce: R) -> T
+where
+    T: Clone + Send,
+    F: Fn(usize, usize) -> T + Sync,
+    R: Fn(T, T) -> T + Sync,
+{
+    let bounds = chunk_bounds(len);
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        return bounds
+            .into_par_iter()
+            .map(|(start, end)| f(start, end))
+            .reduce(|| identity.clone(), |a, b| reduce(a, b));
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        bounds
+            .into_iter()
+            .fold(identity, |acc, (start, end)| reduce(acc, f(start, end)))
+    }
+}
@@ -0,0 +1,61 @@
+// Integration tests for the `kem` crate trait adapters
+// These tests are gated behind the `with_curves` feature
+
+#![cfg(feature = "with_curves")]
+
+mod mimc_circuit;
+
+use ark_bls12_381::{Bls12_381, Fr};
+use ark_std::rand::{Rng, SeedableRng};
+use kem::{Decapsulate, Encapsulate};
+use mimc_circuit::{MiMCCircuit, MIMC_ROUNDS};
+use zkenc_core::{DecapsulatingKey, EncapsulatingKey};
+
+#[test]
+fn test_kem_adapter_roundtrip() {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(0u64);
+    let constants: Vec<Fr> = (0..MIMC_ROUNDS).map(|_| rng.gen()).collect();
+
+    let xl = Fr::from(42u64);
+    let xr = Fr::from(99u64);
+    let output = MiMCCircuit::mimc_native(xl, xr, &constants);
+
+    let encapsulating_key =
+        EncapsulatingKey(MiMCCircuit::new(None, None, Some(output), constants.clone()));
+    let (ciphertext, key1) = encapsulating_key.encapsulate(&mut rng).unwrap();
+
+    let decapsulating_key = DecapsulatingKey(MiMCCircuit::new(
+        Some(xl),
+        Some(xr),
+        Some(output),
+        constants,
+    ));
+    let key2 = decapsulating_key.decapsulate(&ciphertext).unwrap();
+
+    assert_eq!(key1, key2, "Decapsulate should recover the same key as Encapsulate");
+}
+
+#[test]
+fn test_kem_adapter_wrong_witness_fails() {
+    let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(1u64);
+    let constants: Vec<Fr> = (0..MIMC_ROUNDS).map(|_| rng.gen()).collect();
+
+    let xl = Fr::from(42u64);
+    let xr = Fr::from(99u64);
+    let output = MiMCCircuit::mimc_native(xl, xr, &constants);
+
+    let encapsulating_key =
+        EncapsulatingKey(MiMCCircuit::new(None, None, Some(output), constants.clone()));
+    let (ciphertext, _key) = encapsulating_key.encapsulate(&mut rng).unwrap();
+
+    // Wrong preimage: xl/xr don't actually hash to `output`, so the witness
+    // assignment fails constraint satisfaction inside `decap`.
+    let wrong_decapsulating_key = DecapsulatingKey(MiMCCircuit::new(
+        Some(Fr::from(100u64)),
+        Some(Fr::from(200u64)),
+        Some(output),
+        constants,
+    ));
+
+    assert!(wrong_decapsulating_key.decapsulate(&ciphertext).is_err());
+}
@@ -1,23 +1,28 @@
 //! zkenc-js: WASM bindings for zkenc-core
 //!
 //! This module provides JavaScript/WASM interface for witness encryption.
-//! It implements R1CS parsing and circuit construction to work with Circom circuits.
-//!
-//! Note: This duplicates parsing logic from zkenc-cli for independence -
-//! zkenc-js and zkenc-cli are parallel consumers of zkenc-core.
-
-use ark_bn254::{Bn254, Fr};
-use ark_ff::PrimeField;
-use ark_relations::gr1cs::{
-    ConstraintSynthesizer, ConstraintSystemRef, LinearCombination, SynthesisError, Variable,
-    R1CS_PREDICATE_LABEL,
-};
+//! R1CS/witness parsing and the circom `ConstraintSynthesizer` bridge live
+//! in `zkenc_core::circom`, which this crate uses directly rather than
+//! parsing R1CS/witness files itself - zkenc-cli does not share this parser,
+//! it maintains its own (`r1cs.rs`/`witness.rs`/`circuit.rs`) with support
+//! `zkenc_core::circom` doesn't have, namely R1CS v2/custom-gates and
+//! `.sym`-named witness assembly. This module adds the WASM-facing curve
+//! dispatch, ciphertext container, and `circuit.json` entry points on top of
+//! `zkenc_core::circom`.
+
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::rngs::StdRng;
 use ark_std::rand::SeedableRng;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
+use zkenc_core::circom::{
+    decode_parsed_circuit, encode_parsed_circuit, parse_r1cs, parse_witness, CircomCircuit,
+    R1csConstraint, R1csHeader,
+};
 use zkenc_core::{decap, encap, Ciphertext};
 
 /// Initialize WASM module with better error messages
@@ -48,324 +53,237 @@ impl EncapResult {
 }
 
 //////////////////////////////////////////////////////////////////////////////
-// R1CS Parsing (duplicated from zkenc-cli for independence)
+// Curve identification
 //////////////////////////////////////////////////////////////////////////////
 
-struct R1csHeader {
-    field_size: u32,
-    n_wires: u32,
-    n_pub_out: u32,
-    n_pub_in: u32,
-    n_constraints: u32,
-}
-
-impl R1csHeader {
-    fn n_public_inputs(&self) -> u32 {
-        self.n_pub_out + self.n_pub_in
-    }
+/// BN254 (alt_bn128) scalar field modulus, little-endian, as stored in `.r1cs` headers.
+const BN254_PRIME_LE: [u8; 32] = [
+    0x01, 0x00, 0x00, 0xf0, 0x93, 0xf5, 0xe1, 0x43, 0x91, 0x70, 0xb9, 0x79, 0x48, 0xe8, 0x33, 0x28,
+    0x5d, 0x58, 0x81, 0x81, 0xb6, 0x45, 0x50, 0xb8, 0x29, 0xa0, 0x31, 0xe1, 0x72, 0x4e, 0x64, 0x30,
+];
+
+/// BLS12-381 scalar field modulus, little-endian, as stored in `.r1cs` headers.
+const BLS12_381_PRIME_LE: [u8; 32] = [
+    0x01, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0x02, 0xa4, 0xbd, 0x53,
+    0x05, 0xd8, 0xa1, 0x09, 0x08, 0xd8, 0x39, 0x33, 0x48, 0x7d, 0x9d, 0x29, 0x53, 0xa7, 0xed, 0x73,
+];
+
+/// Curves whose scalar field we know how to recognize from an R1CS header's prime.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SupportedCurve {
+    Bn254,
+    Bls12_381,
 }
 
-struct R1csConstraint {
-    a_factors: Vec<(u32, Vec<u8>)>,
-    b_factors: Vec<(u32, Vec<u8>)>,
-    c_factors: Vec<(u32, Vec<u8>)>,
-}
-
-fn parse_r1cs(data: &[u8]) -> Result<(R1csHeader, Vec<R1csConstraint>), String> {
-    let mut pos = 0;
-
-    // Helper to read u32
-    let read_u32 = |pos: &mut usize| -> Result<u32, String> {
-        if *pos + 4 > data.len() {
-            return Err("Unexpected end of data".to_string());
+impl SupportedCurve {
+    /// Curve id stored in the ciphertext container header (see
+    /// `serialize_ciphertext`/`deserialize_ciphertext`).
+    fn id(self) -> u8 {
+        match self {
+            SupportedCurve::Bn254 => 0,
+            SupportedCurve::Bls12_381 => 1,
         }
-        let val = u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
-        *pos += 4;
-        Ok(val)
-    };
+    }
 
-    // Helper to read u64
-    let read_u64 = |pos: &mut usize| -> Result<u64, String> {
-        if *pos + 8 > data.len() {
-            return Err("Unexpected end of data".to_string());
+    fn from_id(id: u8) -> Result<Self, String> {
+        match id {
+            0 => Ok(SupportedCurve::Bn254),
+            1 => Ok(SupportedCurve::Bls12_381),
+            other => Err(format!("Unknown curve id in ciphertext: {}", other)),
         }
-        let val = u64::from_le_bytes([
-            data[*pos],
-            data[*pos + 1],
-            data[*pos + 2],
-            data[*pos + 3],
-            data[*pos + 4],
-            data[*pos + 5],
-            data[*pos + 6],
-            data[*pos + 7],
-        ]);
-        *pos += 8;
-        Ok(val)
-    };
-
-    // Check magic "r1cs"
-    if pos + 4 > data.len() || &data[pos..pos + 4] != b"r1cs" {
-        return Err("Invalid R1CS file: wrong magic".to_string());
     }
-    pos += 4;
+}
 
-    // Version must be 1
-    let version = read_u32(&mut pos)?;
-    if version != 1 {
-        return Err(format!("Unsupported R1CS version: {}", version));
+fn identify_curve(prime: &[u8]) -> Result<SupportedCurve, String> {
+    if prime == BN254_PRIME_LE {
+        Ok(SupportedCurve::Bn254)
+    } else if prime == BLS12_381_PRIME_LE {
+        Ok(SupportedCurve::Bls12_381)
+    } else {
+        Err(format!(
+            "Unsupported curve: R1CS prime does not match a known scalar field modulus (0x{})",
+            prime.iter().rev().map(|b| format!("{:02x}", b)).collect::<String>()
+        ))
     }
+}
 
-    // Number of sections
-    let n_sections = read_u32(&mut pos)?;
-
-    // First pass: collect all section positions
-    let mut sections = Vec::new();
-    for _ in 0..n_sections {
-        let section_type = read_u32(&mut pos)?;
-        let section_len = read_u64(&mut pos)? as usize;
-        let section_start = pos;
-        sections.push((section_type, section_len, section_start));
-        pos = section_start + section_len;
-    }
+//////////////////////////////////////////////////////////////////////////////
+// Ciphertext container
+//////////////////////////////////////////////////////////////////////////////
 
-    // Second pass: find and parse header section first
-    let header = {
-        let header_section = sections
-            .iter()
-            .find(|(t, _, _)| *t == 0x01)
-            .ok_or("Header section (type 1) not found")?;
-
-        let mut header_pos = header_section.2;
-        let field_size = read_u32(&mut header_pos)?;
-        let prime_len = field_size as usize;
-        if header_pos + prime_len > data.len() {
-            return Err("Invalid prime length".to_string());
-        }
-        header_pos += prime_len; // Skip prime bytes
-
-        let n_wires = read_u32(&mut header_pos)?;
-        let n_pub_out = read_u32(&mut header_pos)?;
-        let n_pub_in = read_u32(&mut header_pos)?;
-        let _n_prv_in = read_u32(&mut header_pos)?;
-        let _n_labels = read_u64(&mut header_pos)?;
-        let n_constraints = read_u32(&mut header_pos)?;
-
-        R1csHeader {
-            field_size,
-            n_wires,
-            n_pub_out,
-            n_pub_in,
-            n_constraints,
-        }
-    };
+/// Magic bytes identifying a zkenc ciphertext container.
+const CIPHERTEXT_MAGIC: [u8; 4] = *b"zenc";
 
-    // Third pass: parse constraints section
-    let constraints = {
-        let constraints_section = sections
-            .iter()
-            .find(|(t, _, _)| *t == 0x02)
-            .ok_or("Constraints section (type 2) not found")?;
-
-        let mut constraints_pos = constraints_section.2;
-        let mut constraints = Vec::new();
-
-        for _ in 0..header.n_constraints {
-            // Parse A linear combination
-            let a_factors =
-                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
-            // Parse B linear combination
-            let b_factors =
-                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
-            // Parse C linear combination
-            let c_factors =
-                parse_linear_combination(data, &mut constraints_pos, header.field_size)?;
-
-            constraints.push(R1csConstraint {
-                a_factors,
-                b_factors,
-                c_factors,
-            });
-        }
+/// Container format version. Bump whenever the header layout or the
+/// underlying `Ciphertext` encoding changes incompatibly.
+const CIPHERTEXT_FORMAT_VERSION: u8 = 1;
 
-        constraints
-    };
-
-    Ok((header, constraints))
+/// Wrap a compressed `Ciphertext` in a small self-describing container:
+/// `[magic(4)][version(1)][curve_id(1)][length(4 LE)][compressed bytes]`.
+///
+/// This lets `deserialize_ciphertext` reject a version or curve mismatch
+/// up front with a precise error, instead of failing deep inside
+/// `CanonicalDeserialize` (or worse, silently deserializing garbage).
+fn serialize_ciphertext<E: Pairing>(
+    ciphertext: &Ciphertext<E>,
+    curve: SupportedCurve,
+) -> Result<Vec<u8>, String> {
+    let mut body = Vec::new();
+    ciphertext
+        .serialize_compressed(&mut body)
+        .map_err(|e| format!("Ciphertext serialization failed: {:?}", e))?;
+
+    let mut out = Vec::with_capacity(4 + 1 + 1 + 4 + body.len());
+    out.extend_from_slice(&CIPHERTEXT_MAGIC);
+    out.push(CIPHERTEXT_FORMAT_VERSION);
+    out.push(curve.id());
+    out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
 }
 
-fn parse_linear_combination(
+/// Inverse of `serialize_ciphertext`: validates the magic, format version,
+/// and curve id before attempting to deserialize the inner ciphertext.
+fn deserialize_ciphertext<E: Pairing>(
     data: &[u8],
-    pos: &mut usize,
-    field_size: u32,
-) -> Result<Vec<(u32, Vec<u8>)>, String> {
-    if *pos + 4 > data.len() {
-        return Err("Unexpected end of data in LC".to_string());
+    expected_curve: SupportedCurve,
+) -> Result<Ciphertext<E>, String> {
+    if data.len() < 10 {
+        return Err("Ciphertext container too short".to_string());
     }
-    let n_factors =
-        u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
-    *pos += 4;
-
-    let mut factors = Vec::new();
-    for _ in 0..n_factors {
-        if *pos + 4 > data.len() {
-            return Err("Unexpected end of data reading wire id".to_string());
-        }
-        let wire_id =
-            u32::from_le_bytes([data[*pos], data[*pos + 1], data[*pos + 2], data[*pos + 3]]);
-        *pos += 4;
 
-        let value_len = field_size as usize;
-        if *pos + value_len > data.len() {
-            return Err("Unexpected end of data reading factor value".to_string());
-        }
-        let value = data[*pos..*pos + value_len].to_vec();
-        *pos += value_len;
-
-        factors.push((wire_id, value));
+    if data[0..4] != CIPHERTEXT_MAGIC {
+        return Err("Invalid ciphertext: wrong magic bytes".to_string());
     }
 
-    Ok(factors)
-}
-
-//////////////////////////////////////////////////////////////////////////////
-// Witness Parsing (snarkjs wtns format)
-//////////////////////////////////////////////////////////////////////////////
-
-fn parse_witness(data: &[u8]) -> Result<Vec<Fr>, String> {
-    let mut pos = 0;
-
-    // Check magic "wtns"
-    if pos + 4 > data.len() || &data[pos..pos + 4] != b"wtns" {
-        return Err("Invalid witness file: wrong magic".to_string());
+    let version = data[4];
+    if version != CIPHERTEXT_FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported ciphertext format version: {} (expected {})",
+            version, CIPHERTEXT_FORMAT_VERSION
+        ));
     }
-    pos += 4;
 
-    // Version
-    let version = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-    pos += 4;
-    if version != 2 {
-        return Err(format!("Unsupported witness version: {}", version));
+    let curve = SupportedCurve::from_id(data[5])?;
+    if curve != expected_curve {
+        return Err("Ciphertext curve does not match the R1CS circuit's curve".to_string());
     }
 
-    // Number of sections
-    let n_sections = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-    pos += 4;
-
-    let mut witness: Vec<Fr> = Vec::new();
-    let mut n8 = 0usize;
-
-    for _ in 0..n_sections {
-        let section_type =
-            u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
-        pos += 4;
-
-        let section_len = u64::from_le_bytes([
-            data[pos],
-            data[pos + 1],
-            data[pos + 2],
-            data[pos + 3],
-            data[pos + 4],
-            data[pos + 5],
-            data[pos + 6],
-            data[pos + 7],
-        ]) as usize;
-        pos += 8;
-
-        let section_end = pos + section_len;
-
-        if section_type == 1 {
-            // Header section
-            n8 = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]])
-                as usize;
-        } else if section_type == 2 {
-            // Witness values section - contains raw witness data (field_size * n_witness bytes)
-            // Read all witness values directly
-            while pos + n8 <= section_end {
-                let mut bytes = vec![0u8; 32];
-                let copy_len = n8.min(32);
-                bytes[..copy_len].copy_from_slice(&data[pos..pos + copy_len]);
-
-                witness.push(Fr::from_le_bytes_mod_order(&bytes));
-                pos += n8;
-            }
-        }
-
-        pos = section_end;
-    }
+    let length = u32::from_le_bytes([data[6], data[7], data[8], data[9]]) as usize;
+    let body = data
+        .get(10..10 + length)
+        .ok_or("Ciphertext container truncated")?;
 
-    Ok(witness)
+    Ciphertext::<E>::deserialize_compressed(body)
+        .map_err(|e| format!("Ciphertext deserialization failed: {:?}", e))
 }
 
 //////////////////////////////////////////////////////////////////////////////
-// CircomCircuit implementation
+// R1CS JSON Parsing (snarkjs/zkutil `circuit.json` format)
 //////////////////////////////////////////////////////////////////////////////
 
-struct CircomCircuit {
-    header: R1csHeader,
-    constraints: Vec<R1csConstraint>,
-    witness: HashMap<u32, Fr>,
+/// Shape of the `circuit.json` export some circom/snarkjs tooling produces
+/// instead of the binary `.r1cs` file: constraints as three wireIndex ->
+/// decimal-string coefficient maps, plus the witness-layout counts.
+#[derive(serde::Deserialize)]
+struct CircuitJson {
+    constraints: Vec<[HashMap<String, String>; 3]>,
+    #[serde(rename = "nPubInputs")]
+    n_pub_inputs: u32,
+    #[serde(rename = "nOutputs")]
+    n_outputs: u32,
+    #[serde(rename = "nVars")]
+    n_vars: u32,
 }
 
-impl CircomCircuit {
-    fn bytes_to_fr(bytes: &[u8]) -> Fr {
-        let mut bytes_array = [0u8; 32];
-        let len = bytes.len().min(32);
-        bytes_array[..len].copy_from_slice(&bytes[..len]);
-        Fr::from_le_bytes_mod_order(&bytes_array)
+/// `circuit.json` carries no field modulus, so (as with the rest of the
+/// circom/zkutil tooling it comes from) we assume BN254.
+fn decimal_str_to_field_bytes(value: &str) -> Result<Vec<u8>, String> {
+    use ark_bn254::Fr;
+    use std::str::FromStr;
+
+    let trimmed = value.trim();
+    let field_value = match trimmed.strip_prefix('-') {
+        Some(magnitude) => -Fr::from_str(magnitude)
+            .map_err(|_| format!("Invalid field element: {}", value))?,
+        None => {
+            Fr::from_str(trimmed).map_err(|_| format!("Invalid field element: {}", value))?
+        }
+    };
+
+    let mut bytes = Vec::new();
+    field_value
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| format!("Failed to serialize field element: {:?}", e))?;
+    Ok(bytes)
+}
+
+fn parse_lc_map(map: &HashMap<String, String>) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    let mut factors = Vec::with_capacity(map.len());
+    for (wire_id_str, coeff_str) in map {
+        let wire_id: u32 = wire_id_str
+            .parse()
+            .map_err(|_| format!("Invalid wire index: {}", wire_id_str))?;
+        factors.push((wire_id, decimal_str_to_field_bytes(coeff_str)?));
     }
+    factors.sort_by_key(|(wire_id, _)| *wire_id);
+    Ok(factors)
 }
 
-impl ConstraintSynthesizer<Fr> for CircomCircuit {
-    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
-        // Allocate all variables
-        let mut variables: HashMap<u32, Variable> = HashMap::new();
-        variables.insert(0, Variable::One);
-
-        // Allocate public inputs
-        let n_public = self.header.n_public_inputs();
-        for wire_id in 1..=n_public {
-            let value = self.witness.get(&wire_id).copied();
-            let var = cs.new_input_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
-            variables.insert(wire_id, var);
-        }
+/// Parse a snarkjs/zkutil `circuit.json` document into the same
+/// `(R1csHeader, Vec<R1csConstraint>)` shape `zkenc_core::circom::parse_r1cs`
+/// produces from the binary `.r1cs` format, so both feed the same
+/// downstream circuit code.
+///
+/// The JSON format is always BN254 (it carries no prime), so the returned
+/// header's `prime` is set to `BN254_PRIME_LE`.
+fn parse_r1cs_json(json: &str) -> Result<(R1csHeader, Vec<R1csConstraint>), String> {
+    let circuit: CircuitJson =
+        serde_json::from_str(json).map_err(|e| format!("Invalid circuit.json: {}", e))?;
+
+    let header = R1csHeader {
+        field_size: 32,
+        prime: BN254_PRIME_LE.to_vec(),
+        n_wires: circuit.n_vars,
+        n_pub_out: circuit.n_outputs,
+        n_pub_in: circuit.n_pub_inputs,
+        n_constraints: circuit.constraints.len() as u32,
+        wire_to_label: (0..circuit.n_vars as u64).collect(),
+    };
 
-        // Allocate private witnesses
-        for wire_id in (n_public + 1)..self.header.n_wires {
-            let value = self.witness.get(&wire_id).copied();
-            let var = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
-            variables.insert(wire_id, var);
-        }
+    let mut constraints = Vec::with_capacity(circuit.constraints.len());
+    for [a, b, c] in &circuit.constraints {
+        constraints.push(R1csConstraint {
+            a_factors: parse_lc_map(a)?,
+            b_factors: parse_lc_map(b)?,
+            c_factors: parse_lc_map(c)?,
+        });
+    }
 
-        // Add constraints
-        for constraint in self.constraints {
-            let a_lc = build_lc(&constraint.a_factors, &variables);
-            let b_lc = build_lc(&constraint.b_factors, &variables);
-            let c_lc = build_lc(&constraint.c_factors, &variables);
-
-            let boxed: Vec<Box<dyn FnOnce() -> LinearCombination<Fr>>> = vec![
-                Box::new(move || a_lc),
-                Box::new(move || b_lc),
-                Box::new(move || c_lc),
-            ];
-            cs.enforce_constraint(R1CS_PREDICATE_LABEL, boxed)?;
-        }
+    Ok((header, constraints))
+}
 
-        Ok(())
-    }
+//////////////////////////////////////////////////////////////////////////////
+// RNG helpers
+//////////////////////////////////////////////////////////////////////////////
+
+/// A fresh, OS-seeded RNG for non-deterministic `wasm_encap*` calls.
+fn random_rng() -> Result<StdRng, JsValue> {
+    let mut seed = [0u8; 32];
+    getrandom::getrandom(&mut seed)
+        .map_err(|e| JsValue::from_str(&format!("Random generation failed: {}", e)))?;
+    Ok(StdRng::from_seed(seed))
 }
 
-fn build_lc(
-    factors: &[(u32, Vec<u8>)],
-    variables: &HashMap<u32, Variable>,
-) -> LinearCombination<Fr> {
-    let mut lc = LinearCombination::zero();
-    for (wire_id, coeff_bytes) in factors {
-        if let Some(&var) = variables.get(wire_id) {
-            let coeff = CircomCircuit::bytes_to_fr(coeff_bytes);
-            lc = lc + (coeff, var);
-        }
-    }
-    lc
+/// A deterministic RNG seeded from caller-supplied bytes, for browser test
+/// suites that need reproducible ciphertexts. `seed` is truncated to its
+/// first 32 bytes, or zero-padded to 32 bytes if shorter, so any length of
+/// caller input is accepted.
+fn seeded_rng(seed: &[u8]) -> StdRng {
+    let mut bytes = [0u8; 32];
+    let len = seed.len().min(32);
+    bytes[..len].copy_from_slice(&seed[..len]);
+    StdRng::from_seed(bytes)
 }
 
 //////////////////////////////////////////////////////////////////////////////
@@ -382,48 +300,84 @@ fn build_lc(
 /// Ciphertext and 32-byte symmetric key
 #[wasm_bindgen]
 pub fn wasm_encap(r1cs_bytes: &[u8], witness_bytes: &[u8]) -> Result<EncapResult, JsValue> {
-    // Parse R1CS
+    let mut rng = random_rng()?;
+    encap_from_r1cs_bytes(r1cs_bytes, witness_bytes, &mut rng)
+}
+
+/// Same as [`wasm_encap`], but seeded from caller-supplied bytes instead of
+/// the OS RNG, so tests can assert on a reproducible ciphertext.
+#[wasm_bindgen]
+pub fn wasm_encap_with_seed(
+    r1cs_bytes: &[u8],
+    witness_bytes: &[u8],
+    seed: &[u8],
+) -> Result<EncapResult, JsValue> {
+    let mut rng = seeded_rng(seed);
+    encap_from_r1cs_bytes(r1cs_bytes, witness_bytes, &mut rng)
+}
+
+fn encap_from_r1cs_bytes(
+    r1cs_bytes: &[u8],
+    witness_bytes: &[u8],
+    rng: &mut StdRng,
+) -> Result<EncapResult, JsValue> {
     let (header, constraints) = parse_r1cs(r1cs_bytes)
         .map_err(|e| JsValue::from_str(&format!("R1CS parse error: {}", e)))?;
 
+    let curve = identify_curve(&header.prime).map_err(|e| JsValue::from_str(&e))?;
+
+    match curve {
+        SupportedCurve::Bn254 => {
+            encap_for_curve::<Bn254>(curve, header, constraints, witness_bytes, rng)
+        }
+        SupportedCurve::Bls12_381 => {
+            encap_for_curve::<Bls12_381>(curve, header, constraints, witness_bytes, rng)
+        }
+    }
+}
+
+/// Run `encap` for a specific pairing-friendly curve once the R1CS header's
+/// prime has identified it.
+fn encap_for_curve<E: Pairing>(
+    curve: SupportedCurve,
+    header: R1csHeader,
+    constraints: Vec<R1csConstraint>,
+    witness_bytes: &[u8],
+    rng: &mut StdRng,
+) -> Result<EncapResult, JsValue> {
     // Parse witness file
-    let witness_values = parse_witness(witness_bytes)
+    let witness_values = parse_witness::<E::ScalarField>(witness_bytes)
         .map_err(|e| JsValue::from_str(&format!("Witness parse error: {}", e)))?;
 
     // Extract only public inputs from witness
     // Wire 0 = constant 1, Wires 1..n_pub = public inputs
     let n_pub = header.n_public_inputs() as usize;
-    
+
+    // A `.wtns` file orders its values by label id, not wire index, so the
+    // type-3 wire-to-label map (or the identity mapping if it was absent)
+    // tells us which wire each entry actually belongs to.
+    let label_to_wire = header.label_to_wire();
+
     // Create witness map with constant and public inputs only
     let mut witness_map = HashMap::new();
-    for i in 0..=(n_pub as u32) {
-        if (i as usize) < witness_values.len() {
-            witness_map.insert(i, witness_values[i as usize]);
+    for label in 0..=(n_pub as u64) {
+        if let (Some(&wire_id), Some(&value)) =
+            (label_to_wire.get(&label), witness_values.get(label as usize))
+        {
+            witness_map.insert(wire_id, value);
         }
     }
 
     // Create circuit with only public inputs assigned
-    let circuit = CircomCircuit {
-        header,
-        constraints,
-        witness: witness_map,
-    };
-
-    // Generate random seed
-    let mut seed = [0u8; 32];
-    getrandom::getrandom(&mut seed)
-        .map_err(|e| JsValue::from_str(&format!("Random generation failed: {}", e)))?;
-    let mut rng = StdRng::from_seed(seed);
+    let circuit = CircomCircuit::<E::ScalarField>::new(header, constraints, witness_map);
 
     // Perform encapsulation
-    let (ciphertext, key) = encap::<Bn254, _, _>(circuit, &mut rng)
+    let (ciphertext, key) = encap::<E, _, _>(circuit, rng)
         .map_err(|e| JsValue::from_str(&format!("Encapsulation failed: {:?}", e)))?;
 
-    // Serialize ciphertext
-    let mut ct_bytes = Vec::new();
-    ciphertext
-        .serialize_compressed(&mut ct_bytes)
-        .map_err(|e| JsValue::from_str(&format!("Serialization failed: {:?}", e)))?;
+    // Wrap the ciphertext in a self-describing container so a later decap
+    // call can reject a version/curve mismatch up front.
+    let ct_bytes = serialize_ciphertext(&ciphertext, curve).map_err(|e| JsValue::from_str(&e))?;
 
     Ok(EncapResult {
         ciphertext: ct_bytes,
@@ -450,8 +404,33 @@ pub fn wasm_decap(
     let (header, constraints) = parse_r1cs(r1cs_bytes)
         .map_err(|e| JsValue::from_str(&format!("R1CS parse error: {}", e)))?;
 
+    let curve = identify_curve(&header.prime).map_err(|e| JsValue::from_str(&e))?;
+
+    match curve {
+        SupportedCurve::Bn254 => {
+            decap_for_curve::<Bn254>(curve, header, constraints, witness_bytes, ciphertext_bytes)
+        }
+        SupportedCurve::Bls12_381 => decap_for_curve::<Bls12_381>(
+            curve,
+            header,
+            constraints,
+            witness_bytes,
+            ciphertext_bytes,
+        ),
+    }
+}
+
+/// Run `decap` for a specific pairing-friendly curve once the R1CS header's
+/// prime has identified it.
+fn decap_for_curve<E: Pairing>(
+    curve: SupportedCurve,
+    header: R1csHeader,
+    constraints: Vec<R1csConstraint>,
+    witness_bytes: &[u8],
+    ciphertext_bytes: &[u8],
+) -> Result<Vec<u8>, JsValue> {
     // Parse witness
-    let witness_values = parse_witness(witness_bytes)
+    let witness_values = parse_witness::<E::ScalarField>(witness_bytes)
         .map_err(|e| JsValue::from_str(&format!("Witness parse error: {}", e)))?;
 
     if witness_values.len() != header.n_wires as usize {
@@ -462,30 +441,176 @@ pub fn wasm_decap(
         )));
     }
 
-    // Create witness map with all values (for decap)
+    // Create witness map with all values (for decap), resolving each entry's
+    // wire index through the label map rather than assuming label order
+    // matches wire order.
+    let label_to_wire = header.label_to_wire();
     let mut witness_map = HashMap::new();
-    for (idx, val) in witness_values.iter().enumerate() {
-        witness_map.insert(idx as u32, *val);
+    for (label, val) in witness_values.iter().enumerate() {
+        if let Some(&wire_id) = label_to_wire.get(&(label as u64)) {
+            witness_map.insert(wire_id, *val);
+        }
     }
 
     // Create circuit with full witness
-    let circuit = CircomCircuit {
-        header,
-        constraints,
-        witness: witness_map,
-    };
+    let circuit = CircomCircuit::<E::ScalarField>::new(header, constraints, witness_map);
 
-    // Deserialize ciphertext
-    let ciphertext = Ciphertext::<Bn254>::deserialize_compressed(ciphertext_bytes)
-        .map_err(|e| JsValue::from_str(&format!("Ciphertext deserialization failed: {:?}", e)))?;
+    // Deserialize ciphertext, rejecting a version or curve mismatch up front.
+    let ciphertext =
+        deserialize_ciphertext::<E>(ciphertext_bytes, curve).map_err(|e| JsValue::from_str(&e))?;
 
     // Perform decapsulation
-    let key = decap::<Bn254, _>(circuit, &ciphertext)
+    let key = decap::<E, _>(circuit, &ciphertext)
         .map_err(|e| JsValue::from_str(&format!("Decapsulation failed: {:?}", e)))?;
 
     Ok(key.0.to_vec())
 }
 
-// Note: flatten_json function was removed as it's no longer needed.
-// encap now uses witness file with sym-based mapping for correct signal ordering.
-// This ensures JSON key order does not affect wire index mapping.
+/// Perform encapsulation from a snarkjs/zkutil `circuit.json` document
+/// instead of a binary `.r1cs` file, for browser users who only have the
+/// JSON circuit artifact.
+///
+/// # Arguments
+/// * `circuit_json` - `circuit.json` contents
+/// * `witness_bytes` - Witness file bytes (snarkjs wtns format) containing public inputs
+///
+/// # Returns
+/// Ciphertext and 32-byte symmetric key
+#[wasm_bindgen]
+pub fn wasm_encap_json(circuit_json: &str, witness_bytes: &[u8]) -> Result<EncapResult, JsValue> {
+    let mut rng = random_rng()?;
+    let (header, constraints) =
+        parse_r1cs_json(circuit_json).map_err(|e| JsValue::from_str(&e))?;
+    encap_for_curve::<Bn254>(SupportedCurve::Bn254, header, constraints, witness_bytes, &mut rng)
+}
+
+/// Same as [`wasm_encap_json`], but seeded from caller-supplied bytes
+/// instead of the OS RNG, so tests can assert on a reproducible ciphertext.
+#[wasm_bindgen]
+pub fn wasm_encap_json_with_seed(
+    circuit_json: &str,
+    witness_bytes: &[u8],
+    seed: &[u8],
+) -> Result<EncapResult, JsValue> {
+    let mut rng = seeded_rng(seed);
+    let (header, constraints) =
+        parse_r1cs_json(circuit_json).map_err(|e| JsValue::from_str(&e))?;
+    encap_for_curve::<Bn254>(SupportedCurve::Bn254, header, constraints, witness_bytes, &mut rng)
+}
+
+/// Perform decapsulation from a snarkjs/zkutil `circuit.json` document
+/// instead of a binary `.r1cs` file.
+///
+/// # Arguments
+/// * `circuit_json` - `circuit.json` contents
+/// * `witness_bytes` - Witness file bytes (snarkjs wtns format)
+/// * `ciphertext_bytes` - Ciphertext from encapsulation
+///
+/// # Returns
+/// 32-byte symmetric key
+#[wasm_bindgen]
+pub fn wasm_decap_json(
+    circuit_json: &str,
+    witness_bytes: &[u8],
+    ciphertext_bytes: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    let (header, constraints) =
+        parse_r1cs_json(circuit_json).map_err(|e| JsValue::from_str(&e))?;
+
+    decap_for_curve::<Bn254>(
+        SupportedCurve::Bn254,
+        header,
+        constraints,
+        witness_bytes,
+        ciphertext_bytes,
+    )
+}
+
+//////////////////////////////////////////////////////////////////////////////
+// Pre-parsed circuit caching
+//////////////////////////////////////////////////////////////////////////////
+
+/// Parse a binary `.r1cs` file once into a compact form that can be cached
+/// and reused across many `wasm_encap_parsed`/`wasm_decap_parsed` calls.
+///
+/// `parse_r1cs` depends only on the circuit, never on a witness or
+/// instance, so a browser host that repeatedly encaps/decaps against the
+/// same circuit can call this once, persist the returned bytes (e.g. in
+/// IndexedDB), and hand them to `wasm_encap_parsed`/`wasm_decap_parsed` on
+/// every later call instead of re-parsing the original `.r1cs` file.
+///
+/// # Arguments
+/// * `r1cs_bytes` - R1CS circuit file bytes
+///
+/// # Returns
+/// Serialized parsed-circuit bytes, opaque to the caller.
+#[wasm_bindgen]
+pub fn wasm_parse_r1cs(r1cs_bytes: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let (header, constraints) = parse_r1cs(r1cs_bytes).map_err(|e| JsValue::from_str(&e))?;
+    Ok(encode_parsed_circuit(&header, &constraints))
+}
+
+/// Same as [`wasm_encap`], but takes the bytes [`wasm_parse_r1cs`] returned
+/// instead of a raw `.r1cs` file, skipping the R1CS parse.
+#[wasm_bindgen]
+pub fn wasm_encap_parsed(parsed_circuit: &[u8], witness_bytes: &[u8]) -> Result<EncapResult, JsValue> {
+    let mut rng = random_rng()?;
+    encap_from_parsed_circuit(parsed_circuit, witness_bytes, &mut rng)
+}
+
+/// Same as [`wasm_encap_parsed`], but seeded from caller-supplied bytes
+/// instead of the OS RNG, so tests can assert on a reproducible ciphertext.
+#[wasm_bindgen]
+pub fn wasm_encap_parsed_with_seed(
+    parsed_circuit: &[u8],
+    witness_bytes: &[u8],
+    seed: &[u8],
+) -> Result<EncapResult, JsValue> {
+    let mut rng = seeded_rng(seed);
+    encap_from_parsed_circuit(parsed_circuit, witness_bytes, &mut rng)
+}
+
+fn encap_from_parsed_circuit(
+    parsed_circuit: &[u8],
+    witness_bytes: &[u8],
+    rng: &mut StdRng,
+) -> Result<EncapResult, JsValue> {
+    let (header, constraints) =
+        decode_parsed_circuit(parsed_circuit).map_err(|e| JsValue::from_str(&e))?;
+    let curve = identify_curve(&header.prime).map_err(|e| JsValue::from_str(&e))?;
+
+    match curve {
+        SupportedCurve::Bn254 => {
+            encap_for_curve::<Bn254>(curve, header, constraints, witness_bytes, rng)
+        }
+        SupportedCurve::Bls12_381 => {
+            encap_for_curve::<Bls12_381>(curve, header, constraints, witness_bytes, rng)
+        }
+    }
+}
+
+/// Same as [`wasm_decap`], but takes the bytes [`wasm_parse_r1cs`] returned
+/// instead of a raw `.r1cs` file, skipping the R1CS parse.
+#[wasm_bindgen]
+pub fn wasm_decap_parsed(
+    parsed_circuit: &[u8],
+    witness_bytes: &[u8],
+    ciphertext_bytes: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    let (header, constraints) =
+        decode_parsed_circuit(parsed_circuit).map_err(|e| JsValue::from_str(&e))?;
+    let curve = identify_curve(&header.prime).map_err(|e| JsValue::from_str(&e))?;
+
+    match curve {
+        SupportedCurve::Bn254 => {
+            decap_for_curve::<Bn254>(curve, header, constraints, witness_bytes, ciphertext_bytes)
+        }
+        SupportedCurve::Bls12_381 => decap_for_curve::<Bls12_381>(
+            curve,
+            header,
+            constraints,
+            witness_bytes,
+            ciphertext_bytes,
+        ),
+    }
+}